@@ -0,0 +1,117 @@
+//! Frames of coloured characters, one per grid cell, shown in the terminal
+//! or written out as PPM images. Shared by the days that can animate.
+
+use std::{io::Write, path::PathBuf, thread, time::Duration};
+
+/// A character and its colour for one cell of a frame.
+pub type Cell = (char, [u8; 3]);
+
+pub enum Sink {
+    /// Redraw the frame in place with ANSI colours, pausing between frames.
+    Terminal { delay: Duration },
+    /// Write `frame_0000.ppm`, `frame_0001.ppm`, ... into a directory, with
+    /// every cell drawn as a `scale` × `scale` square.
+    Ppm { dir: PathBuf, scale: usize },
+}
+
+pub struct Animator {
+    sink: Sink,
+    frame: usize,
+}
+
+impl Animator {
+    /// `--animate` with an optional `--delay` in milliseconds, or `--frames`
+    /// followed by the output directory; `None` when neither is given.
+    pub fn from_args(args: &[String]) -> Option<Self> {
+        let flag = |name: &str| {
+            args.iter()
+                .position(|a| a == name)
+                .and_then(|i| args.get(i + 1))
+        };
+        let sink = if let Some(dir) = flag("--frames") {
+            std::fs::create_dir_all(dir).unwrap();
+            Sink::Ppm {
+                dir: dir.into(),
+                scale: 4,
+            }
+        } else if args.iter().any(|a| a == "--animate") {
+            let ms = flag("--delay").map_or(200, |d| d.parse().unwrap());
+            Sink::Terminal {
+                delay: Duration::from_millis(ms),
+            }
+        } else {
+            return None;
+        };
+        Some(Self { sink, frame: 0 })
+    }
+
+    pub fn show(&mut self, title: &str, cells: &[Vec<Cell>]) {
+        match &self.sink {
+            Sink::Terminal { delay } => {
+                let mut out = std::io::stdout().lock();
+                write!(out, "\x1b[2J\x1b[H{title}\n{}", render_ansi(cells)).unwrap();
+                out.flush().unwrap();
+                thread::sleep(*delay);
+            }
+            Sink::Ppm { dir, scale } => {
+                let path = dir.join(format!("frame_{:04}.ppm", self.frame));
+                std::fs::write(path, render_ppm(cells, *scale)).unwrap();
+            }
+        }
+        self.frame += 1;
+    }
+}
+
+fn render_ansi(cells: &[Vec<Cell>]) -> String {
+    let mut out = String::new();
+    for row in cells {
+        for &(c, [r, g, b]) in row {
+            out.push_str(&format!("\x1b[38;2;{r};{g};{b}m{c}"));
+        }
+        out.push_str("\x1b[0m\n");
+    }
+    out
+}
+
+/// Binary PPM; short rows are padded with black.
+fn render_ppm(cells: &[Vec<Cell>], scale: usize) -> Vec<u8> {
+    let width = cells.iter().map(|r| r.len()).max().unwrap_or(0);
+    let mut out = format!("P6\n{} {}\n255\n", width * scale, cells.len() * scale).into_bytes();
+    for row in cells {
+        let mut line = Vec::with_capacity(width * scale * 3);
+        for j in 0..width {
+            let rgb = row.get(j).map_or([0; 3], |&(_, rgb)| rgb);
+            for _ in 0..scale {
+                line.extend_from_slice(&rgb);
+            }
+        }
+        for _ in 0..scale {
+            out.extend_from_slice(&line);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let cells = vec![
+            vec![('@', [255, 0, 0]), ('.', [0, 0, 255])],
+            vec![('@', [1, 2, 3])],
+        ];
+        let ansi = render_ansi(&cells);
+        assert!(ansi.starts_with("\x1b[38;2;255;0;0m@\x1b[38;2;0;0;255m.\x1b[0m\n"));
+        assert_eq!(ansi.lines().count(), 2);
+
+        let ppm = render_ppm(&cells, 2);
+        let header = b"P6\n4 4\n255\n";
+        assert_eq!(&ppm[..header.len()], header);
+        let pixels = &ppm[header.len()..];
+        assert_eq!(pixels.len(), 4 * 4 * 3);
+        assert_eq!(&pixels[..12], &[255, 0, 0, 255, 0, 0, 0, 0, 255, 0, 0, 255]);
+        assert_eq!(&pixels[24..36], &[1, 2, 3, 1, 2, 3, 0, 0, 0, 0, 0, 0]);
+    }
+}
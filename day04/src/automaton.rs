@@ -0,0 +1,331 @@
+use std::{ops::RangeInclusive, str::FromStr};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Neighbourhood {
+    /// Every cell within the given Chebyshev distance.
+    Moore(i64),
+    /// Every cell within the given Manhattan distance.
+    VonNeumann(i64),
+    Custom(Vec<(i64, i64)>),
+}
+
+impl Neighbourhood {
+    fn offsets(&self) -> Vec<(i64, i64)> {
+        let within = |r: i64, keep: fn(i64, i64, i64) -> bool| {
+            (-r..=r)
+                .flat_map(|di| (-r..=r).map(move |dj| (di, dj)))
+                .filter(|&(di, dj)| (di, dj) != (0, 0) && keep(di, dj, r))
+                .collect()
+        };
+        match self {
+            Neighbourhood::Moore(r) => within(*r, |_, _, _| true),
+            Neighbourhood::VonNeumann(r) => within(*r, |di, dj, r| di.abs() + dj.abs() <= r),
+            Neighbourhood::Custom(offsets) => offsets.clone(),
+        }
+    }
+}
+
+/// `moore`, `vonneumann`, either followed by `:radius`, or `custom:` and a
+/// list of `di,dj` offsets separated by `;`.
+impl FromStr for Neighbourhood {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (kind, arg) = s.split_once(':').unwrap_or((s, "1"));
+        let radius = || arg.parse().map_err(|_| format!("invalid radius `{arg}`"));
+        match kind {
+            "moore" => Ok(Neighbourhood::Moore(radius()?)),
+            "vonneumann" => Ok(Neighbourhood::VonNeumann(radius()?)),
+            "custom" => arg
+                .split(';')
+                .map(|o| {
+                    let (di, dj) = o.split_once(',').ok_or(format!("invalid offset `{o}`"))?;
+                    Ok((
+                        di.trim()
+                            .parse()
+                            .map_err(|_| format!("invalid offset `{o}`"))?,
+                        dj.trim()
+                            .parse()
+                            .map_err(|_| format!("invalid offset `{o}`"))?,
+                    ))
+                })
+                .collect::<Result<_, String>>()
+                .map(Neighbourhood::Custom),
+            _ => Err(format!(
+                "unknown neighbourhood `{kind}`, expected moore, vonneumann or custom"
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Boundary {
+    /// Cells past the edge are empty.
+    #[default]
+    Bounded,
+    /// The grid wraps around in both directions.
+    Toroidal,
+}
+
+impl FromStr for Boundary {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bounded" => Ok(Boundary::Bounded),
+            "toroidal" => Ok(Boundary::Toroidal),
+            _ => Err(format!(
+                "unknown boundary `{s}`, expected bounded or toroidal"
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Update {
+    /// Every cell of a step sees the grid as it was before the step.
+    #[default]
+    Synchronous,
+    /// Cells are visited in reading order and see removals made earlier in
+    /// the same step.
+    Asynchronous,
+}
+
+impl FromStr for Update {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sync" => Ok(Update::Synchronous),
+            "async" => Ok(Update::Asynchronous),
+            _ => Err(format!("unknown update `{s}`, expected sync or async")),
+        }
+    }
+}
+
+/// An automaton in which occupied cells are removed, never added: a cell
+/// survives a step while its number of occupied neighbours stays in
+/// `survive`.
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub neighbourhood: Neighbourhood,
+    pub survive: RangeInclusive<usize>,
+    pub boundary: Boundary,
+    pub update: Update,
+    /// Stop after this many steps even if cells are still being removed.
+    pub max_steps: Option<usize>,
+}
+
+/// Day04's rule run to completion: a roll goes once fewer than four of its
+/// eight neighbours hold rolls.
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            neighbourhood: Neighbourhood::Moore(1),
+            survive: 4..=usize::MAX,
+            boundary: Boundary::Bounded,
+            update: Update::Synchronous,
+            max_steps: None,
+        }
+    }
+}
+
+pub struct Grid {
+    width: usize,
+    height: usize,
+    cells: Vec<bool>,
+}
+
+impl Grid {
+    pub fn parse(input: &str) -> Self {
+        let lines = input.lines().map(str::trim).collect::<Vec<_>>();
+        let width = lines.iter().map(|l| l.len()).max().unwrap_or(0);
+        let mut cells = vec![false; width * lines.len()];
+        for (i, line) in lines.iter().enumerate() {
+            for (j, c) in line.bytes().enumerate() {
+                cells[i * width + j] = c == b'@';
+            }
+        }
+        Self {
+            width,
+            height: lines.len(),
+            cells,
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.cells.iter().filter(|&&c| c).count()
+    }
+
+    fn neighbour(&self, i: usize, j: usize, (di, dj): (i64, i64), boundary: Boundary) -> bool {
+        let (h, w) = (self.height as i64, self.width as i64);
+        let (ni, nj) = (i as i64 + di, j as i64 + dj);
+        let (ni, nj) = match boundary {
+            Boundary::Bounded if !(0..h).contains(&ni) || !(0..w).contains(&nj) => return false,
+            Boundary::Bounded => (ni, nj),
+            Boundary::Toroidal => (ni.rem_euclid(h), nj.rem_euclid(w)),
+        };
+        self.cells[ni as usize * self.width + nj as usize]
+    }
+
+    /// The offsets wrapped around the torus, leaving out those that land on
+    /// the same cell as an earlier one or on the centre cell, which happens
+    /// once the grid is smaller than the neighbourhood.
+    fn wrapped_offsets(&self, offsets: &[(i64, i64)]) -> Vec<(i64, i64)> {
+        if self.cells.is_empty() {
+            return Vec::new();
+        }
+        let (h, w) = (self.height as i64, self.width as i64);
+        let mut wrapped = Vec::with_capacity(offsets.len());
+        for &(di, dj) in offsets {
+            let o = (di.rem_euclid(h), dj.rem_euclid(w));
+            if o != (0, 0) && !wrapped.contains(&o) {
+                wrapped.push(o);
+            }
+        }
+        wrapped
+    }
+}
+
+/// Cells removed in one step and the number of cells left after it.
+#[derive(Debug, PartialEq)]
+pub struct Step {
+    pub removed: Vec<(usize, usize)>,
+    pub remaining: usize,
+}
+
+impl Config {
+    /// Runs until a step removes nothing or `max_steps` is reached. That
+    /// last, empty step is not part of the history.
+    pub fn run(&self, grid: &mut Grid) -> Vec<Step> {
+        let offsets = match self.boundary {
+            Boundary::Bounded => self.neighbourhood.offsets(),
+            Boundary::Toroidal => grid.wrapped_offsets(&self.neighbourhood.offsets()),
+        };
+        let mut history = Vec::new();
+        let mut remaining = grid.count();
+        while self.max_steps.is_none_or(|max| history.len() < max) {
+            let mut removed = Vec::new();
+            for i in 0..grid.height {
+                for j in 0..grid.width {
+                    if !grid.cells[i * grid.width + j] {
+                        continue;
+                    }
+                    let n = offsets
+                        .iter()
+                        .filter(|&&o| grid.neighbour(i, j, o, self.boundary))
+                        .count();
+                    if !self.survive.contains(&n) {
+                        removed.push((i, j));
+                        if self.update == Update::Asynchronous {
+                            grid.cells[i * grid.width + j] = false;
+                        }
+                    }
+                }
+            }
+            if removed.is_empty() {
+                break;
+            }
+            for &(i, j) in &removed {
+                grid.cells[i * grid.width + j] = false;
+            }
+            remaining -= removed.len();
+            history.push(Step { removed, remaining });
+        }
+        history
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{part2, tests::EXAMPLE, tests::random_grid};
+
+    #[test]
+    fn test_day04_configs() {
+        let part1 = Config {
+            max_steps: Some(1),
+            ..Config::default()
+        };
+        let history = part1.run(&mut Grid::parse(EXAMPLE));
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].removed.len(), 13);
+
+        let mut grid = Grid::parse(EXAMPLE);
+        let history = Config::default().run(&mut grid);
+        let removed = history.iter().map(|s| s.removed.len()).collect::<Vec<_>>();
+        assert_eq!(removed.iter().sum::<usize>(), 43);
+        assert_eq!(history.last().unwrap().remaining, grid.count());
+
+        // Asynchronous updates peel faster but reach the same core.
+        let config = Config {
+            update: Update::Asynchronous,
+            ..Config::default()
+        };
+        for seed in 0..20 {
+            let input = random_grid(seed, 12, 15, 200);
+            let history = config.run(&mut Grid::parse(&input));
+            let total = history.iter().map(|s| s.removed.len()).sum::<usize>();
+            assert_eq!(total as u64, part2(&input));
+        }
+    }
+
+    #[test]
+    fn test_neighbourhoods_and_boundaries() {
+        assert_eq!(Neighbourhood::Moore(1).offsets().len(), 8);
+        assert_eq!(Neighbourhood::Moore(2).offsets().len(), 24);
+        assert_eq!(Neighbourhood::VonNeumann(1).offsets().len(), 4);
+        assert_eq!(Neighbourhood::VonNeumann(2).offsets().len(), 12);
+        assert_eq!(
+            "custom:0,1;0,-1".parse(),
+            Ok(Neighbourhood::Custom(vec![(0, 1), (0, -1)]))
+        );
+        assert!("hex".parse::<Neighbourhood>().is_err());
+
+        // A full 3×3 block: bounded, the corners only have three neighbours;
+        // on a torus every cell sees the whole block.
+        let block = "@@@\n@@@\n@@@";
+        let history = Config::default().run(&mut Grid::parse(block));
+        assert_eq!(history[0].removed, vec![(0, 0), (0, 2), (2, 0), (2, 2)]);
+        let torus = Config {
+            boundary: Boundary::Toroidal,
+            ..Config::default()
+        };
+        assert!(torus.run(&mut Grid::parse(block)).is_empty());
+
+        // On a torus one row high, the rows above and below are the row
+        // itself, so each roll of three only has two distinct neighbours.
+        let history = torus.run(&mut Grid::parse("@@@"));
+        assert_eq!(
+            history,
+            vec![Step {
+                removed: vec![(0, 0), (0, 1), (0, 2)],
+                remaining: 0
+            }]
+        );
+        // Two rows high, both wrapped rows are the other row: five
+        // neighbours, not eight.
+        let five = Config {
+            survive: 5..=5,
+            ..torus.clone()
+        };
+        assert!(five.run(&mut Grid::parse("@@@@\n@@@@")).is_empty());
+
+        // With only horizontal neighbours, every roll of a row of three
+        // except the middle one goes first, then the middle one.
+        let row = Config {
+            neighbourhood: Neighbourhood::Custom(vec![(0, -1), (0, 1)]),
+            survive: 2..=2,
+            ..Config::default()
+        };
+        let history = row.run(&mut Grid::parse("@@@"));
+        assert_eq!(history.len(), 2);
+        assert_eq!(
+            history[1],
+            Step {
+                removed: vec![(0, 1)],
+                remaining: 0
+            }
+        );
+    }
+}
@@ -0,0 +1,136 @@
+/// Rolls packed one bit per cell, each row padded to a whole number of
+/// `u64` words. Neighbour counts for a whole word of cells at once come from
+/// shifted copies of the three rows around it fed through bit-sliced adders.
+#[derive(Clone, PartialEq)]
+pub struct Bitboard {
+    width: usize,
+    height: usize,
+    stride: usize,
+    words: Vec<u64>,
+}
+
+impl Bitboard {
+    pub fn parse(input: &str) -> Self {
+        let lines = input.lines().map(str::trim).collect::<Vec<_>>();
+        let width = lines.iter().map(|l| l.len()).max().unwrap_or(0);
+        let height = lines.len();
+        let stride = width.div_ceil(64);
+        let mut words = vec![0; stride * height];
+        for (i, line) in lines.iter().enumerate() {
+            for (j, c) in line.bytes().enumerate() {
+                if c == b'@' {
+                    words[i * stride + j / 64] |= 1 << (j % 64);
+                }
+            }
+        }
+        Self {
+            width,
+            height,
+            stride,
+            words,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&w| w == 0)
+    }
+
+    pub fn count_ones(&self) -> u64 {
+        self.words.iter().map(|w| w.count_ones() as u64).sum()
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, i: usize, j: usize) -> bool {
+        i < self.height
+            && j < self.width
+            && self.words[i * self.stride + j / 64] >> (j % 64) & 1 == 1
+    }
+
+    fn row(&self, i: usize) -> &[u64] {
+        &self.words[i * self.stride..(i + 1) * self.stride]
+    }
+
+    /// Rolls with fewer than four of their eight neighbours occupied.
+    pub fn accessible(&self) -> Self {
+        let empty = vec![0; self.stride];
+        let mut out = vec![0; self.words.len()];
+        for i in 0..self.height {
+            let above = if i > 0 { self.row(i - 1) } else { &empty };
+            let below = if i + 1 < self.height {
+                self.row(i + 1)
+            } else {
+                &empty
+            };
+            let here = self.row(i);
+            for k in 0..self.stride {
+                // Bit j of `west(r)` is cell j - 1 of row r, of `east(r)` cell j + 1.
+                let west = |r: &[u64]| r[k] << 1 | if k > 0 { r[k - 1] >> 63 } else { 0 };
+                let east = |r: &[u64]| r[k] >> 1 | r.get(k + 1).map_or(0, |w| w << 63);
+                let neighbours = [
+                    west(above),
+                    above[k],
+                    east(above),
+                    west(here),
+                    east(here),
+                    west(below),
+                    below[k],
+                    east(below),
+                ];
+                // Two-bit counter per cell plus a sticky bit for the carry
+                // out of it, which is set once a cell has four neighbours.
+                let (mut s0, mut s1, mut s2) = (0u64, 0u64, 0u64);
+                for x in neighbours {
+                    let c0 = s0 & x;
+                    s0 ^= x;
+                    let c1 = s1 & c0;
+                    s1 ^= c0;
+                    s2 |= c1;
+                }
+                out[i * self.stride + k] = here[k] & !s2;
+            }
+        }
+        Self {
+            words: out,
+            ..*self
+        }
+    }
+
+    /// Clears every cell set in `other`.
+    pub fn remove(&mut self, other: &Self) {
+        for (w, o) in self.words.iter_mut().zip(&other.words) {
+            *w &= !o;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{part1_hashset, part2, part2_rounds, tests::random_grid};
+
+    #[test]
+    fn test_matches_hashset_on_random_grids() {
+        for seed in 0..60 {
+            let (rows, cols) = (
+                1 + seed as usize % 13,
+                [1, 5, 63, 64, 65, 130][seed as usize % 6],
+            );
+            let fill = [64, 128, 180, 220, 250][seed as usize % 5];
+            let input = random_grid(seed, rows, cols, fill);
+            let board = Bitboard::parse(&input);
+            assert_eq!(
+                board.accessible().count_ones(),
+                part1_hashset(&input),
+                "seed {seed}"
+            );
+            assert_eq!(part2(&input), part2_rounds(&input), "seed {seed}");
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use std::fmt::Write;
+
+use crate::automaton::{Config, Grid};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Depth {
+    Empty,
+    /// Removed in this round, counting from 1.
+    Removed(usize),
+    /// Never removed: part of the stable core.
+    Core,
+}
+
+/// The round in which every roll was removed while running part 2.
+pub struct DepthMap {
+    width: usize,
+    rows: Vec<Vec<Depth>>,
+    /// Rolls removed in each round, the first round at index 0.
+    round_counts: Vec<usize>,
+}
+
+impl DepthMap {
+    pub fn new(input: &str) -> Self {
+        let mut rows = input
+            .lines()
+            .map(|line| {
+                line.trim()
+                    .bytes()
+                    .map(|c| if c == b'@' { Depth::Core } else { Depth::Empty })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let width = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+        let history = Config::default().run(&mut Grid::parse(input));
+        for (round, step) in history.iter().enumerate() {
+            for &(i, j) in &step.removed {
+                rows[i][j] = Depth::Removed(round + 1);
+            }
+        }
+        Self {
+            width,
+            rows,
+            round_counts: history.iter().map(|s| s.removed.len()).collect(),
+        }
+    }
+
+    pub fn round_counts(&self) -> &[usize] {
+        &self.round_counts
+    }
+
+    pub fn core_size(&self) -> usize {
+        self.rows
+            .iter()
+            .flatten()
+            .filter(|&&d| d == Depth::Core)
+            .count()
+    }
+
+    /// One character per cell: `.` for no roll, `#` for the core, and the
+    /// round in base 36 (`1`–`9`, then `a`–`z`, `+` beyond that).
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        for row in &self.rows {
+            for j in 0..self.width {
+                out.push(match row.get(j).copied().unwrap_or(Depth::Empty) {
+                    Depth::Empty => '.',
+                    Depth::Core => '#',
+                    Depth::Removed(r) => std::char::from_digit(r as u32, 36).unwrap_or('+'),
+                });
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Plain greyscale PGM: black where there is no roll, getting lighter
+    /// with each round, and white for the core.
+    pub fn to_pgm(&self) -> String {
+        let white = self.round_counts.len() + 1;
+        let mut out = String::new();
+        writeln!(out, "P2").unwrap();
+        writeln!(out, "{} {}", self.width, self.rows.len()).unwrap();
+        writeln!(out, "{white}").unwrap();
+        for row in &self.rows {
+            let line = (0..self.width)
+                .map(|j| match row.get(j).copied().unwrap_or(Depth::Empty) {
+                    Depth::Empty => 0,
+                    Depth::Removed(r) => r,
+                    Depth::Core => white,
+                })
+                .map(|v| v.to_string())
+                .collect::<Vec<_>>();
+            writeln!(out, "{}", line.join(" ")).unwrap();
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::EXAMPLE;
+
+    #[test]
+    fn test_depth_map() {
+        let map = DepthMap::new(EXAMPLE);
+        assert_eq!(map.round_counts(), [13, 12, 7, 5, 2, 1, 1, 1, 1]);
+        assert_eq!(map.core_size(), 71 - 43);
+        let text = map.to_text();
+        assert_eq!(text.lines().next(), Some("..11.1121."));
+        assert_eq!(text.matches('#').count(), 28);
+
+        let map = DepthMap::new("@@@\n@@@\n@@@\n");
+        assert_eq!(map.to_text(), "121\n232\n121\n");
+        assert_eq!(map.to_pgm(), "P2\n3 3\n4\n1 2 1\n2 3 2\n1 2 1\n");
+    }
+}
@@ -0,0 +1,62 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::NEIGHTBORD;
+
+/// Removes accessible rolls until none is left, returning how many went.
+///
+/// Every roll keeps a count of its neighbours; removing a roll only updates
+/// its eight neighbours, and a roll is queued once, the moment its count
+/// drops below 4. This touches each roll a constant number of times instead
+/// of rescanning the whole grid every round.
+pub fn remove_all(grid: &HashSet<(i64, i64)>) -> u64 {
+    let mut counts = grid
+        .iter()
+        .map(|&(i, j)| {
+            let n = NEIGHTBORD
+                .into_iter()
+                .filter(|(di, dj)| grid.contains(&(i + di, j + dj)))
+                .count();
+            ((i, j), n)
+        })
+        .collect::<HashMap<_, _>>();
+    let mut worklist = counts
+        .iter()
+        .filter(|&(_, &n)| n < 4)
+        .map(|(&p, _)| p)
+        .collect::<Vec<_>>();
+    let mut removed = 0;
+    while let Some((i, j)) = worklist.pop() {
+        counts.remove(&(i, j));
+        removed += 1;
+        for (di, dj) in NEIGHTBORD {
+            if let Some(n) = counts.get_mut(&(i + di, j + dj)) {
+                *n -= 1;
+                // Rolls already below 4 are queued, only catch the crossing.
+                if *n == 3 {
+                    worklist.push((i + di, j + dj));
+                }
+            }
+        }
+    }
+    removed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse, part2_rounds, tests::random_grid};
+
+    #[test]
+    fn test_matches_rounds_on_random_grids() {
+        for seed in 0..200 {
+            let (rows, cols) = (1 + seed as usize % 17, 1 + seed as usize * 7 % 23);
+            let fill = [64, 128, 180, 220, 250][seed as usize % 5];
+            let input = random_grid(seed, rows, cols, fill);
+            assert_eq!(
+                remove_all(&parse(&input)),
+                part2_rounds(&input),
+                "seed {seed}:\n{input}"
+            );
+        }
+    }
+}
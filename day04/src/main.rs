@@ -1,5 +1,12 @@
 use std::{collections::HashSet, path::PathBuf, time::Instant};
 
+mod automaton;
+mod bitboard;
+mod depth;
+mod frontier;
+
+use bitboard::Bitboard;
+
 const NEIGHTBORD: [(i64, i64); 8] = [
     (-1, 0),
     (1, 0),
@@ -27,7 +34,7 @@ fn parse(input: &str) -> HashSet<(i64, i64)> {
         .collect()
 }
 
-fn part1(input: &str) -> u64 {
+fn part1_hashset(input: &str) -> u64 {
     let pos = parse(input);
     pos.iter()
         .filter(|&&(i, j)| {
@@ -40,7 +47,8 @@ fn part1(input: &str) -> u64 {
         .count() as u64
 }
 
-fn part2(input: &str) -> u64 {
+/// Removes every accessible roll at once, round after round.
+fn part2_rounds(input: &str) -> u64 {
     let mut grid = parse(input);
     let mut count = 0;
     loop {
@@ -65,6 +73,110 @@ fn part2(input: &str) -> u64 {
     }
 }
 
+fn part1(input: &str) -> u64 {
+    Bitboard::parse(input).accessible().count_ones()
+}
+
+fn part2(input: &str) -> u64 {
+    part2_with(input, |_, _| {})
+}
+
+/// Part 2 with a hook that sees the board and the rolls about to go before
+/// every round, and the final board with nothing left to remove.
+fn part2_with(input: &str, mut on_round: impl FnMut(&Bitboard, &Bitboard)) -> u64 {
+    let mut board = Bitboard::parse(input);
+    let mut count = 0;
+    loop {
+        let accessible = board.accessible();
+        on_round(&board, &accessible);
+        if accessible.is_empty() {
+            return count;
+        }
+        count += accessible.count_ones();
+        board.remove(&accessible);
+    }
+}
+
+fn part1_automaton(input: &str) -> u64 {
+    let config = automaton::Config {
+        max_steps: Some(1),
+        ..Default::default()
+    };
+    let history = config.run(&mut automaton::Grid::parse(input));
+    history.first().map_or(0, |step| step.removed.len() as u64)
+}
+
+fn part2_automaton(input: &str) -> u64 {
+    let history = automaton::Config::default().run(&mut automaton::Grid::parse(input));
+    history.iter().map(|step| step.removed.len() as u64).sum()
+}
+
+/// Runs the removal automaton configured from the command line and prints
+/// how many cells each step removed.
+fn run_automaton(input: &str, args: &[String]) -> Result<(), String> {
+    let flag = |name: &str| {
+        args.iter()
+            .position(|a| a == name)
+            .and_then(|i| args.get(i + 1))
+    };
+    let mut config = automaton::Config::default();
+    if let Some(n) = flag("--neighbourhood") {
+        config.neighbourhood = n.parse()?;
+    }
+    if let Some(range) = flag("--survive") {
+        let (min, max) = range
+            .split_once("..")
+            .ok_or(format!("invalid range `{range}`, expected min..max"))?;
+        let min = min
+            .parse()
+            .map_err(|_| format!("invalid minimum `{min}`"))?;
+        let max = if max.is_empty() {
+            usize::MAX
+        } else {
+            max.parse()
+                .map_err(|_| format!("invalid maximum `{max}`"))?
+        };
+        config.survive = min..=max;
+    }
+    if let Some(b) = flag("--boundary") {
+        config.boundary = b.parse()?;
+    }
+    if let Some(u) = flag("--update") {
+        config.update = u.parse()?;
+    }
+    if let Some(n) = flag("--steps") {
+        config.max_steps = Some(n.parse().map_err(|_| format!("invalid steps `{n}`"))?);
+    }
+    let mut grid = automaton::Grid::parse(input);
+    println!("Start: {} cells", grid.count());
+    for (k, step) in config.run(&mut grid).iter().enumerate() {
+        println!(
+            "Step {}: removed {}, {} left",
+            k + 1,
+            step.removed.len(),
+            step.remaining
+        );
+    }
+    Ok(())
+}
+
+/// Rolls about to be removed in red, the others in grey.
+fn round_frame(board: &Bitboard, accessible: &Bitboard) -> Vec<Vec<anim::Cell>> {
+    (0..board.height())
+        .map(|i| {
+            (0..board.width())
+                .map(
+                    |j| match (board.contains(i, j), accessible.contains(i, j)) {
+                        (_, true) => ('@', [230, 60, 50]),
+                        (true, false) => ('@', [170, 170, 170]),
+                        (false, _) => ('.', [50, 50, 50]),
+                    },
+                )
+                .collect()
+        })
+        .collect()
+}
+
 fn main() {
     let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
         .parent()
@@ -72,6 +184,56 @@ fn main() {
         .join("data")
         .join("day04.dat");
     let input = std::fs::read_to_string(path).unwrap();
+    let args: Vec<String> = std::env::args().collect();
+    let flag = |name: &str| {
+        args.iter()
+            .position(|a| a == name)
+            .and_then(|i| args.get(i + 1))
+    };
+    if args.iter().any(|a| a == "--automaton") {
+        if let Err(e) = run_automaton(&input, &args) {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+        return;
+    }
+    if let Some(mut animator) = anim::Animator::from_args(&args) {
+        let mut round = 0;
+        let total = part2_with(&input, |board, accessible| {
+            round += 1;
+            let title = format!("Round {round}: removing {}", accessible.count_ones());
+            animator.show(&title, &round_frame(board, accessible));
+        });
+        println!("Part 2: {total}");
+        return;
+    }
+    if let Some(out) = flag("--depth") {
+        let map = depth::DepthMap::new(&input);
+        for (round, count) in map.round_counts().iter().enumerate() {
+            println!("Round {}: removed {count}", round + 1);
+        }
+        println!("Core: {}", map.core_size());
+        let image = if out.ends_with(".pgm") {
+            map.to_pgm()
+        } else {
+            map.to_text()
+        };
+        std::fs::write(out, image).unwrap();
+        println!("Wrote {out}");
+        return;
+    }
+    type Solver = fn(&str) -> u64;
+    let (part1, part2): (Solver, Solver) = match flag("--method").map_or("bitboard", |m| m.as_str())
+    {
+        "bitboard" => (part1, part2),
+        "hashset" => (part1_hashset, part2_rounds),
+        "frontier" => (part1_hashset, |input| frontier::remove_all(&parse(input))),
+        "automaton" => (part1_automaton, part2_automaton),
+        m => {
+            eprintln!("unknown method `{m}`, expected bitboard, hashset, frontier or automaton");
+            std::process::exit(1);
+        }
+    };
     let t = Instant::now();
     println!("Part 1: {}", part1(&input));
     println!("Part 1 took: {:?}", t.elapsed());
@@ -84,7 +246,24 @@ fn main() {
 mod tests {
     use super::*;
 
-    const EXAMPLE: &str = r#"..@@.@@@@.
+    /// Grid of the given size where each cell holds a roll with probability
+    /// `fill / 256`, from a xorshift generator so the tests need no crates.
+    pub fn random_grid(seed: u64, rows: usize, cols: usize, fill: u64) -> String {
+        let mut state = seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1;
+        let mut grid = String::new();
+        for _ in 0..rows {
+            for _ in 0..cols {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                grid.push(if state % 256 < fill { '@' } else { '.' });
+            }
+            grid.push('\n');
+        }
+        grid
+    }
+
+    pub const EXAMPLE: &str = r#"..@@.@@@@.
 @@@.@.@.@@
 @@@@@.@.@@
 @.@@@@..@.
@@ -98,10 +277,14 @@ mod tests {
     #[test]
     fn test_part1() {
         assert_eq!(part1(EXAMPLE), 13);
+        assert_eq!(part1_hashset(EXAMPLE), 13);
+        assert_eq!(part1_automaton(EXAMPLE), 13);
     }
 
     #[test]
     fn test_part2() {
         assert_eq!(part2(EXAMPLE), 43);
+        assert_eq!(part2_rounds(EXAMPLE), 43);
+        assert_eq!(part2_automaton(EXAMPLE), 43);
     }
 }
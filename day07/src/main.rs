@@ -1,60 +1,73 @@
-use std::{
-    collections::{HashMap, HashSet},
-    path::PathBuf,
-    time::Instant,
-};
+mod manifold;
+mod tracer;
 
-fn part1(input: &str) -> u64 {
-    let mut lines = input.trim().lines();
-    let mut beams = HashSet::new();
-    let mut split = 0;
-    beams.insert(
-        lines
-            .next()
-            .unwrap()
-            .chars()
-            .enumerate()
-            .find(|(_, c)| c == &'S')
-            .unwrap()
-            .0,
-    );
-    for line in lines {
-        for (i, c) in line.chars().enumerate() {
-            if c == '^' && beams.remove(&i) {
-                beams.insert(i - 1);
-                beams.insert(i + 1);
-                split += 1;
-            }
-        }
-    }
-    split
+use manifold::{Boundary, Manifold};
+
+use std::{collections::HashMap, path::PathBuf, time::Instant};
+
+fn part1(input: &str, boundary: Boundary) -> u64 {
+    Manifold::parse(input).propagate(boundary, |_, _| {}).splits
 }
 
-fn part2(input: &str) -> u64 {
-    let mut lines = input.trim().lines();
-    let mut beams = HashMap::new();
-    beams.insert(
-        lines
-            .next()
-            .unwrap()
-            .chars()
-            .enumerate()
-            .find(|(_, c)| c == &'S')
-            .unwrap()
-            .0,
-        1,
-    );
-    for line in lines {
-        for (i, c) in line.chars().enumerate() {
-            if c == '^'
-                && let Some(count) = beams.remove(&i)
-            {
-                *beams.entry(i - 1).or_default() += count;
-                *beams.entry(i + 1).or_default() += count;
-            }
-        }
+/// Every timeline counts, including those whose beam left through a side.
+fn part2(input: &str, boundary: Boundary) -> u64 {
+    let result = Manifold::parse(input).propagate(boundary, |_, _| {});
+    result.timelines + result.escaped_timelines
+}
+
+/// The manifold with the beams of every row seen so far drawn in, brighter
+/// where more timelines pass.
+fn beam_frame(canvas: &[Vec<char>], seen: &[HashMap<usize, u64>]) -> Vec<Vec<anim::Cell>> {
+    let max = seen
+        .iter()
+        .flat_map(|r| r.values())
+        .max()
+        .copied()
+        .unwrap_or(1);
+    canvas
+        .iter()
+        .enumerate()
+        .map(|(row, line)| {
+            line.iter()
+                .enumerate()
+                .map(|(i, &c)| match seen.get(row).and_then(|r| r.get(&i)) {
+                    Some(&n) if c == '.' => {
+                        let t = (n as f64).ln_1p() / (max as f64).ln_1p();
+                        ('|', [255, (120.0 + 135.0 * t) as u8, (60.0 * t) as u8])
+                    }
+                    _ if c == '^' => ('^', [90, 160, 255]),
+                    _ if c == 'S' => ('S', [255, 255, 255]),
+                    _ => ('.', [50, 50, 50]),
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Traces the grid with every `S` plus each `--source row,col,dir` as a
+/// source, and prints the part 1 and part 2 style counts.
+fn run_tracer(input: &str, args: &[String]) -> Result<(), String> {
+    let mut beams = tracer::Tracer::parse(input);
+    for (k, _) in args.iter().enumerate().filter(|(_, a)| *a == "--source") {
+        let spec = args.get(k + 1).ok_or("--source needs row,col,dir")?;
+        let parts = spec.split(',').collect::<Vec<_>>();
+        let [r, c, dir] = parts[..] else {
+            return Err(format!("invalid source `{spec}`, expected row,col,dir"));
+        };
+        let index = |s: &str| s.parse().map_err(|_| format!("invalid index `{s}`"));
+        beams.add_source(tracer::Beam {
+            pos: (index(r)?, index(c)?),
+            dir: dir.parse()?,
+        })?;
     }
-    beams.values().sum()
+    let summary = beams.trace();
+    println!("Splits: {}", summary.splits);
+    println!("Energized: {}", summary.energized);
+    match summary.timelines {
+        Ok(n) => println!("Timelines: {n}"),
+        Err(e) => println!("Timelines: infinite, {e}"),
+    }
+    Ok(())
 }
 
 fn main() {
@@ -64,19 +77,61 @@ fn main() {
         .join("data")
         .join("day07.dat");
     let input = std::fs::read_to_string(path).unwrap();
+    let args: Vec<String> = std::env::args().collect();
+    if args.iter().any(|a| a == "--trace") {
+        if let Err(e) = run_tracer(&input, &args) {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+        return;
+    }
+    let boundary = match args.iter().position(|a| a == "--boundary") {
+        Some(i) => match args.get(i + 1).map(|b| b.parse()) {
+            Some(Ok(boundary)) => boundary,
+            Some(Err(e)) => {
+                eprintln!("{e}");
+                std::process::exit(1);
+            }
+            None => {
+                eprintln!("--boundary needs absorb, reflect or wrap");
+                std::process::exit(1);
+            }
+        },
+        None => Boundary::default(),
+    };
+    if let Some(mut animator) = anim::Animator::from_args(&args) {
+        let canvas = input
+            .trim()
+            .lines()
+            .map(|l| l.chars().collect())
+            .collect::<Vec<Vec<_>>>();
+        let mut seen = Vec::new();
+        let result = Manifold::parse(&input).propagate(boundary, |row, beams| {
+            seen.push(beams.clone());
+            let title = format!("Row {row}: {} timelines", beams.values().sum::<u64>());
+            animator.show(&title, &beam_frame(&canvas, &seen));
+        });
+        println!("Part 2: {}", result.timelines + result.escaped_timelines);
+        return;
+    }
     let t = Instant::now();
-    println!("Part 1: {}", part1(&input));
+    println!("Part 1: {}", part1(&input, boundary));
     println!("Part 1 took: {:?}", t.elapsed());
     let t = Instant::now();
-    println!("Part 2: {}", part2(&input));
+    println!("Part 2: {}", part2(&input, boundary));
     println!("Part 2 took: {:?}", t.elapsed());
+    let result = Manifold::parse(&input).propagate(boundary, |_, _| {});
+    println!(
+        "Escaped: {} beams, {} timelines",
+        result.escaped_beams, result.escaped_timelines
+    );
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const EXAMPLE: &str = r#".......S.......
+    pub const EXAMPLE: &str = r#".......S.......
 ...............
 .......^.......
 ...............
@@ -95,11 +150,11 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(EXAMPLE), 21);
+        assert_eq!(part1(EXAMPLE, Boundary::Absorb), 21);
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(EXAMPLE), 40);
+        assert_eq!(part2(EXAMPLE, Boundary::Absorb), 40);
     }
 }
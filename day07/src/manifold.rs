@@ -0,0 +1,143 @@
+use std::{collections::HashMap, str::FromStr};
+
+/// What happens to a beam split off sideways past the edge of the grid.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Boundary {
+    /// The beam leaves the manifold and is counted as escaped.
+    #[default]
+    Absorb,
+    /// The beam bounces off the wall back into the edge column.
+    Reflect,
+    /// The beam comes back in on the opposite side.
+    Wrap,
+}
+
+impl Boundary {
+    /// Column reached by a beam heading for `col`, or `None` if it escapes.
+    fn resolve(self, col: i64, width: usize) -> Option<usize> {
+        let w = width as i64;
+        match self {
+            _ if (0..w).contains(&col) => Some(col as usize),
+            Boundary::Absorb => None,
+            Boundary::Reflect => Some(col.clamp(0, w - 1) as usize),
+            Boundary::Wrap => Some(col.rem_euclid(w) as usize),
+        }
+    }
+}
+
+impl FromStr for Boundary {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "absorb" => Ok(Boundary::Absorb),
+            "reflect" => Ok(Boundary::Reflect),
+            "wrap" => Ok(Boundary::Wrap),
+            _ => Err(format!(
+                "unknown boundary `{s}`, expected absorb, reflect or wrap"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Propagation {
+    /// Splitters hit by at least one beam (part 1).
+    pub splits: u64,
+    /// Timelines in which the beam reaches the last row.
+    pub timelines: u64,
+    /// Beams that left through a side, merging those leaving the same row on
+    /// the same side like beams inside the grid merge.
+    pub escaped_beams: u64,
+    /// Timelines in which the beam left through a side.
+    pub escaped_timelines: u64,
+}
+
+pub struct Manifold {
+    rows: Vec<Vec<u8>>,
+    width: usize,
+    start: usize,
+}
+
+impl Manifold {
+    pub fn parse(input: &str) -> Self {
+        let rows = input
+            .trim()
+            .lines()
+            .map(|l| l.as_bytes().to_vec())
+            .collect::<Vec<_>>();
+        let width = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+        let start = rows[0].iter().position(|&c| c == b'S').unwrap();
+        Self { rows, width, start }
+    }
+
+    /// Sends the beam down from `S` one row at a time, calling `on_row` with
+    /// the number of timelines in each column once the row is done.
+    pub fn propagate(
+        &self,
+        boundary: Boundary,
+        mut on_row: impl FnMut(usize, &HashMap<usize, u64>),
+    ) -> Propagation {
+        let mut result = Propagation::default();
+        let mut beams = HashMap::from([(self.start, 1)]);
+        on_row(0, &beams);
+        for (r, row) in self.rows.iter().enumerate().skip(1) {
+            let mut next = HashMap::with_capacity(beams.len() + 2);
+            let mut escaped = [false; 2];
+            for (&i, &count) in &beams {
+                if row.get(i) != Some(&b'^') {
+                    *next.entry(i).or_default() += count;
+                    continue;
+                }
+                result.splits += 1;
+                for (side, col) in [i as i64 - 1, i as i64 + 1].into_iter().enumerate() {
+                    match boundary.resolve(col, self.width) {
+                        Some(col) => *next.entry(col).or_default() += count,
+                        None => {
+                            escaped[side] = true;
+                            result.escaped_timelines += count;
+                        }
+                    }
+                }
+            }
+            result.escaped_beams += escaped.iter().filter(|&&e| e).count() as u64;
+            beams = next;
+            on_row(r, &beams);
+        }
+        result.timelines = beams.values().sum();
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_boundaries() {
+        // The right-hand beam of the first split hits a splitter in the last
+        // column, the left-hand one a splitter in the first.
+        let input = ".S.\n...\n.^.\n^.^\n...";
+        let manifold = Manifold::parse(input);
+        let run = |b| manifold.propagate(b, |_, _| {});
+        assert_eq!(
+            run(Boundary::Absorb),
+            Propagation {
+                splits: 3,
+                timelines: 2,
+                escaped_beams: 2,
+                escaped_timelines: 2,
+            }
+        );
+        // Both edge beams bounce back into the column of the splitter that
+        // sent them out.
+        let reflect = run(Boundary::Reflect);
+        assert_eq!((reflect.splits, reflect.timelines), (3, 4));
+        assert_eq!(reflect.escaped_timelines, 0);
+        // Wrapping sends each edge beam to the opposite edge column.
+        let wrap = run(Boundary::Wrap);
+        assert_eq!((wrap.timelines, wrap.escaped_beams), (4, 0));
+        assert_eq!("wrap".parse(), Ok(Boundary::Wrap));
+        assert!("bounce".parse::<Boundary>().is_err());
+    }
+}
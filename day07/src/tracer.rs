@@ -0,0 +1,275 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    str::FromStr,
+};
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Dir {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Dir {
+    fn delta(self) -> (i64, i64) {
+        match self {
+            Dir::Up => (-1, 0),
+            Dir::Down => (1, 0),
+            Dir::Left => (0, -1),
+            Dir::Right => (0, 1),
+        }
+    }
+
+    fn vertical(self) -> bool {
+        matches!(self, Dir::Up | Dir::Down)
+    }
+}
+
+impl FromStr for Dir {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "up" => Ok(Dir::Up),
+            "down" => Ok(Dir::Down),
+            "left" => Ok(Dir::Left),
+            "right" => Ok(Dir::Right),
+            _ => Err(format!(
+                "unknown direction `{s}`, expected up, down, left or right"
+            )),
+        }
+    }
+}
+
+/// A beam in a cell, about to be acted on by whatever the cell holds.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Beam {
+    pub pos: (usize, usize),
+    pub dir: Dir,
+}
+
+/// The beams a cell sends on. `^` is day07's splitter: it replaces the beam
+/// by two beams in the neighbouring cells either side of it, still heading
+/// the same way. `|` and `-` split beams crossing them into the two
+/// directions along them and let beams along them through; `/` and `\` are
+/// mirrors and `#` absorbs beams.
+fn act(cell: u8, beam: Beam) -> Vec<(i64, i64, Dir)> {
+    let (r, c) = (beam.pos.0 as i64, beam.pos.1 as i64);
+    let forward = |dir: Dir| {
+        let (dr, dc) = dir.delta();
+        (r + dr, c + dc, dir)
+    };
+    match (cell, beam.dir) {
+        (b'#', _) => vec![],
+        (b'^', dir) if dir.vertical() => vec![(r, c - 1, dir), (r, c + 1, dir)],
+        (b'^', dir) => vec![(r - 1, c, dir), (r + 1, c, dir)],
+        (b'|', dir) if !dir.vertical() => vec![forward(Dir::Up), forward(Dir::Down)],
+        (b'-', dir) if dir.vertical() => vec![forward(Dir::Left), forward(Dir::Right)],
+        (b'/', Dir::Right) | (b'\\', Dir::Left) => vec![forward(Dir::Up)],
+        (b'/', Dir::Left) | (b'\\', Dir::Right) => vec![forward(Dir::Down)],
+        (b'/', Dir::Up) | (b'\\', Dir::Down) => vec![forward(Dir::Right)],
+        (b'/', Dir::Down) | (b'\\', Dir::Up) => vec![forward(Dir::Left)],
+        (_, dir) => vec![forward(dir)],
+    }
+}
+
+fn is_splitter(cell: u8, dir: Dir) -> bool {
+    match cell {
+        b'^' => true,
+        b'|' => !dir.vertical(),
+        b'-' => dir.vertical(),
+        _ => false,
+    }
+}
+
+/// A beam that comes back to a cell it already passed through heading the
+/// same way, which makes the number of timelines infinite.
+#[derive(Debug, PartialEq)]
+pub struct Loop(pub Beam);
+
+impl fmt::Display for Loop {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Beam { pos: (r, c), dir } = self.0;
+        write!(f, "beam loops through ({r}, {c}) heading {dir:?}")
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Summary {
+    /// Splitter cells that split at least one beam (part 1).
+    pub splits: usize,
+    /// Cells crossed by at least one beam.
+    pub energized: usize,
+    /// Distinct paths of a beam from the sources (part 2), or the first
+    /// loop found if some beam cycles forever.
+    pub timelines: Result<u64, Loop>,
+}
+
+pub struct Tracer {
+    rows: Vec<Vec<u8>>,
+    sources: Vec<Beam>,
+}
+
+impl Tracer {
+    /// Every `S` in the grid becomes a source shining down.
+    pub fn parse(input: &str) -> Self {
+        let rows = input
+            .trim()
+            .lines()
+            .map(|l| l.as_bytes().to_vec())
+            .collect::<Vec<_>>();
+        let sources = rows
+            .iter()
+            .enumerate()
+            .flat_map(|(r, row)| {
+                row.iter()
+                    .enumerate()
+                    .filter(|&(_, &c)| c == b'S')
+                    .map(move |(c, _)| Beam {
+                        pos: (r, c),
+                        dir: Dir::Down,
+                    })
+            })
+            .collect();
+        Self { rows, sources }
+    }
+
+    /// Adds a source shining from `beam.pos`, which must be a cell of the
+    /// grid.
+    pub fn add_source(&mut self, beam: Beam) -> Result<(), String> {
+        let (r, c) = beam.pos;
+        if self.rows.get(r).is_none_or(|row| c >= row.len()) {
+            return Err(format!("source ({r}, {c}) is outside the grid"));
+        }
+        self.sources.push(beam);
+        Ok(())
+    }
+
+    fn cell(&self, r: i64, c: i64) -> Option<u8> {
+        let row = self.rows.get(usize::try_from(r).ok()?)?;
+        row.get(usize::try_from(c).ok()?).copied()
+    }
+
+    /// The beams a beam turns into, with `None` for each one leaving the
+    /// grid.
+    fn successors(&self, beam: Beam) -> impl Iterator<Item = Option<Beam>> + '_ {
+        let cell = self.rows[beam.pos.0][beam.pos.1];
+        act(cell, beam).into_iter().map(|(r, c, dir)| {
+            self.cell(r, c).map(|_| Beam {
+                pos: (r as usize, c as usize),
+                dir,
+            })
+        })
+    }
+
+    /// Follows every beam once, merging beams that share a cell and a
+    /// direction.
+    pub fn trace(&self) -> Summary {
+        let mut seen = HashSet::new();
+        let mut stack = self.sources.clone();
+        while let Some(beam) = stack.pop() {
+            if seen.insert(beam) {
+                stack.extend(self.successors(beam).flatten());
+            }
+        }
+        let cells = seen.iter().map(|b| b.pos).collect::<HashSet<_>>();
+        let splits = seen
+            .iter()
+            .filter(|b| is_splitter(self.rows[b.pos.0][b.pos.1], b.dir))
+            .map(|b| b.pos)
+            .collect::<HashSet<_>>();
+        Summary {
+            splits: splits.len(),
+            energized: cells.len(),
+            timelines: self.timelines(),
+        }
+    }
+
+    /// Number of distinct paths from the sources to where a beam leaves the
+    /// grid or is absorbed (part 2), found depth first without recursion so
+    /// that tall grids don't overflow the stack.
+    fn timelines(&self) -> Result<u64, Loop> {
+        let mut paths: HashMap<Beam, u64> = HashMap::new();
+        let mut on_stack = HashSet::new();
+        let mut total = 0;
+        for &source in &self.sources {
+            let mut stack = vec![(source, false)];
+            while let Some((beam, expanded)) = stack.pop() {
+                if expanded {
+                    on_stack.remove(&beam);
+                    let next = self.successors(beam).collect::<Vec<_>>();
+                    let n = if next.is_empty() {
+                        1
+                    } else {
+                        next.iter().map(|b| b.map_or(1, |b| paths[&b])).sum()
+                    };
+                    paths.insert(beam, n);
+                    continue;
+                }
+                if paths.contains_key(&beam) {
+                    continue;
+                }
+                if !on_stack.insert(beam) {
+                    return Err(Loop(beam));
+                }
+                stack.push((beam, true));
+                for next in self.successors(beam).flatten() {
+                    if on_stack.contains(&next) {
+                        return Err(Loop(next));
+                    }
+                    if !paths.contains_key(&next) {
+                        stack.push((next, false));
+                    }
+                }
+            }
+            total += paths[&source];
+        }
+        Ok(total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::EXAMPLE;
+
+    #[test]
+    fn test_day07_semantics() {
+        let tracer = Tracer::parse(EXAMPLE);
+        assert_eq!(tracer.trace().splits, 21);
+        assert_eq!(tracer.timelines(), Ok(40));
+
+        // Two sources, one sideways through a splitter, one absorbed.
+        let mut tracer = Tracer::parse("S..\n-.#\n...");
+        tracer
+            .add_source(Beam {
+                pos: (2, 2),
+                dir: Dir::Left,
+            })
+            .unwrap();
+        assert_eq!(tracer.timelines(), Ok(3));
+        let summary = tracer.trace();
+        assert_eq!((summary.splits, summary.energized), (1, 7));
+        for pos in [(3, 0), (0, 3), (999, 0)] {
+            let beam = Beam {
+                pos,
+                dir: Dir::Down,
+            };
+            assert!(tracer.add_source(beam).is_err());
+        }
+        assert_eq!(tracer.timelines(), Ok(3));
+    }
+
+    #[test]
+    fn test_mirror_loop() {
+        // The splitter sends beams both ways round a ring of mirrors, and
+        // both come back to it along the line it lets through.
+        let tracer = Tracer::parse(".S..\n/-.\\\n....\n\\../");
+        let summary = tracer.trace();
+        assert_eq!((summary.splits, summary.energized), (1, 11));
+        assert!(summary.timelines.is_err());
+        assert_eq!(Tracer::parse(".S\n./").timelines(), Ok(1));
+    }
+}
@@ -0,0 +1,307 @@
+use std::collections::BinaryHeap;
+
+use crate::metric::Metric;
+
+/// One step of single-linkage clustering: the pair of junction boxes whose
+/// connection joined two circuits.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Merge {
+    pub pair: [usize; 2],
+    /// Distance between the two boxes as given by [`Metric::dist`], so
+    /// squared under [`Metric::Euclidean`].
+    pub dist: i64,
+    /// Ids of the merged circuits: `0..n` are single boxes, `n + k` is the
+    /// circuit created by the `k`-th merge.
+    pub clusters: [usize; 2],
+    /// Number of boxes in the new circuit.
+    pub size: usize,
+}
+
+/// Minimum spanning tree of the junction boxes, kept in merge order
+/// so that it doubles as a single-linkage dendrogram.
+pub struct Dendrogram<'a, const N: usize> {
+    points: &'a [[i64; N]],
+    metric: Metric,
+    merges: Vec<Merge>,
+}
+
+struct UnionFind {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+}
+
+impl UnionFind {
+    fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+        }
+    }
+
+    fn find(&mut self, i: usize) -> usize {
+        let mut root = i;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        let mut i = i;
+        while self.parent[i] != root {
+            i = std::mem::replace(&mut self.parent[i], root);
+        }
+        root
+    }
+
+    /// Joins the sets of `i` and `j`, returning the size of the result or
+    /// `None` if they were already joined.
+    fn union(&mut self, i: usize, j: usize) -> Option<usize> {
+        let (ri, rj) = (self.find(i), self.find(j));
+        if ri == rj {
+            return None;
+        }
+        let (big, small) = if self.size[ri] >= self.size[rj] {
+            (ri, rj)
+        } else {
+            (rj, ri)
+        };
+        self.parent[small] = big;
+        self.size[big] += self.size[small];
+        Some(self.size[big])
+    }
+
+    fn sizes(&self) -> Vec<usize> {
+        let mut sizes = (0..self.parent.len())
+            .filter(|&i| self.parent[i] == i)
+            .map(|i| self.size[i])
+            .collect::<Vec<_>>();
+        sizes.sort_unstable_by(|a, b| b.cmp(a));
+        sizes
+    }
+}
+
+/// A pair of boxes `i < j` keyed by distance. Ties are broken by index so that
+/// the order is total and nothing depends on the stability of a sort.
+pub type Pair = (i64, [usize; 2]);
+
+fn pair<const N: usize>(points: &[[i64; N]], metric: Metric, i: usize, j: usize) -> Pair {
+    (metric.dist(&points[i], &points[j]), [i.min(j), i.max(j)])
+}
+
+/// The `k` closest pairs of boxes in increasing order, keeping at most `k`
+/// pairs in memory at once.
+pub fn closest_pairs<const N: usize>(points: &[[i64; N]], metric: Metric, k: usize) -> Vec<Pair> {
+    let mut heap = BinaryHeap::with_capacity(k + 1);
+    for i in 0..points.len() {
+        for j in i + 1..points.len() {
+            let p = pair(points, metric, i, j);
+            if heap.len() < k {
+                heap.push(p);
+            } else if heap.peek().is_some_and(|&top| p < top) {
+                heap.pop();
+                heap.push(p);
+            }
+        }
+    }
+    heap.into_sorted_vec()
+}
+
+/// Circuit sizes, largest first, after connecting the given pairs.
+pub fn circuit_sizes(n: usize, pairs: &[Pair]) -> Vec<usize> {
+    let mut uf = UnionFind::new(n);
+    for &(_, [i, j]) in pairs {
+        uf.union(i, j);
+    }
+    uf.sizes()
+}
+
+/// Circuit of every box after connecting the given pairs, numbered from the
+/// largest circuit down (ties by smallest box index).
+pub fn circuit_labels(n: usize, pairs: impl IntoIterator<Item = [usize; 2]>) -> Vec<usize> {
+    let mut uf = UnionFind::new(n);
+    for [i, j] in pairs {
+        uf.union(i, j);
+    }
+    let roots = (0..n).map(|i| uf.find(i)).collect::<Vec<_>>();
+    let mut first = vec![0; n];
+    for i in (0..n).rev() {
+        first[roots[i]] = i;
+    }
+    let mut order = (0..n).filter(|&i| roots[i] == i).collect::<Vec<_>>();
+    order.sort_by_key(|&r| (std::cmp::Reverse(uf.size[r]), first[r]));
+    let mut label = vec![0; n];
+    for (l, &r) in order.iter().enumerate() {
+        label[r] = l;
+    }
+    roots.iter().map(|&r| label[r]).collect()
+}
+
+impl<'a, const N: usize> Dendrogram<'a, N> {
+    /// Prim's algorithm on the complete graph, in O(n²) time and O(n) memory,
+    /// followed by a replay of the tree edges in increasing order.
+    pub fn build(points: &'a [[i64; N]], metric: Metric) -> Self {
+        let n = points.len();
+        let mut edges = Vec::with_capacity(n.saturating_sub(1));
+        let mut in_tree = vec![false; n];
+        let mut best = vec![(i64::MAX, [usize::MAX; 2]); n];
+        let mut current = 0;
+        for _ in 1..n {
+            in_tree[current] = true;
+            let mut next = None;
+            for v in (0..n).filter(|&v| !in_tree[v]) {
+                best[v] = best[v].min(pair(points, metric, current, v));
+                if next.is_none_or(|u: usize| best[v] < best[u]) {
+                    next = Some(v);
+                }
+            }
+            current = next.unwrap();
+            edges.push(best[current]);
+        }
+        edges.sort_unstable();
+
+        let mut uf = UnionFind::new(n);
+        let mut cluster_of = (0..n).collect::<Vec<_>>();
+        let mut merges = Vec::with_capacity(edges.len());
+        for (dist, [i, j]) in edges {
+            let (ri, rj) = (uf.find(i), uf.find(j));
+            let size = uf.union(i, j).unwrap();
+            let clusters = [cluster_of[ri], cluster_of[rj]];
+            cluster_of[uf.find(i)] = n + merges.len();
+            merges.push(Merge {
+                pair: [i, j],
+                dist,
+                clusters,
+                size,
+            });
+        }
+        Self {
+            points,
+            metric,
+            merges,
+        }
+    }
+
+    /// The spanning tree edges, in the order they merged circuits.
+    pub fn merges(&self) -> &[Merge] {
+        &self.merges
+    }
+
+    fn cut(&self, keep: impl Fn(&Merge) -> bool) -> Vec<usize> {
+        let mut uf = UnionFind::new(self.points.len());
+        for merge in self.merges.iter().take_while(|m| keep(m)) {
+            uf.union(merge.pair[0], merge.pair[1]);
+        }
+        uf.sizes()
+    }
+
+    /// Circuit sizes, largest first, after connecting the `n_pairs` closest
+    /// pairs of boxes under the metric the tree was built with.
+    pub fn cut_at_pairs(&self, n_pairs: usize) -> Vec<usize> {
+        match closest_pairs(self.points, self.metric, n_pairs).last() {
+            Some(&last) => self.cut(|m| (m.dist, m.pair) <= last),
+            None => self.cut(|_| false),
+        }
+    }
+
+    /// Circuit sizes, largest first, after connecting every pair of boxes at
+    /// distance at most `max_dist`. The threshold is a plain distance under
+    /// every metric; it is squared here to compare with Euclidean merges.
+    pub fn cut_at_distance(&self, max_dist: i64) -> Vec<usize> {
+        if max_dist < 0 {
+            return self.cut(|_| false);
+        }
+        let max_dist = match self.metric {
+            Metric::Euclidean => max_dist.saturating_mul(max_dist),
+            _ => max_dist,
+        };
+        self.cut(|m| m.dist <= max_dist)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_input, tests::EXAMPLE};
+
+    fn all_pairs_sorted(points: &[[i64; 3]], metric: Metric) -> Vec<Pair> {
+        let mut pairs = (0..points.len())
+            .flat_map(|i| (i + 1..points.len()).map(move |j| (i, j)))
+            .map(|(i, j)| pair(points, metric, i, j))
+            .collect::<Vec<_>>();
+        pairs.sort();
+        pairs
+    }
+
+    #[test]
+    fn test_dendrogram() {
+        let points = parse_input::<3>(EXAMPLE);
+        let dendrogram = Dendrogram::build(&points, Metric::Euclidean);
+        let merges = dendrogram.merges();
+        assert_eq!(merges.len(), points.len() - 1);
+        assert!(merges.windows(2).all(|w| w[0].dist <= w[1].dist));
+        assert_eq!(merges[0].pair, [0, 19]);
+        assert_eq!(merges[0].clusters, [0, 19]);
+        assert_eq!(merges.last().unwrap().size, points.len());
+
+        assert_eq!(&dendrogram.cut_at_pairs(10)[..3], &[5, 4, 2]);
+        assert_eq!(dendrogram.cut_at_pairs(0), vec![1; points.len()]);
+        let closest = (merges[0].dist as f64).sqrt().ceil() as i64;
+        assert_eq!(dendrogram.cut_at_distance(closest)[0], 2);
+        assert_eq!(dendrogram.cut_at_distance(closest - 1)[0], 1);
+        assert_eq!(dendrogram.cut_at_distance(i64::MAX), vec![points.len()]);
+    }
+
+    #[test]
+    fn test_cut_at_distance_is_not_squared() {
+        // A 3-4-5 triangle plus a far point: the first pair is 5 apart
+        // (25 squared) under Euclidean and 7 apart under Manhattan.
+        let points = [[0, 0], [3, 4], [20, 0]];
+        let euclidean = Dendrogram::build(&points, Metric::Euclidean);
+        assert_eq!(euclidean.merges()[0].dist, 25);
+        assert_eq!(euclidean.cut_at_distance(5), vec![2, 1]);
+        assert_eq!(euclidean.cut_at_distance(4), vec![1, 1, 1]);
+        assert_eq!(euclidean.cut_at_distance(-1), vec![1, 1, 1]);
+        let manhattan = Dendrogram::build(&points, Metric::Manhattan);
+        assert_eq!(manhattan.cut_at_distance(7), vec![2, 1]);
+        assert_eq!(manhattan.cut_at_distance(6), vec![1, 1, 1]);
+    }
+
+    #[test]
+    fn test_bounded_selection_matches_full_sort() {
+        let points = parse_input::<3>(EXAMPLE);
+        for metric in [Metric::Euclidean, Metric::Manhattan, Metric::Chebyshev] {
+            let dendrogram = Dendrogram::build(&points, metric);
+            let all = all_pairs_sorted(&points, metric);
+            for k in 0..=all.len() {
+                assert_eq!(closest_pairs(&points, metric, k), all[..k]);
+                assert_eq!(
+                    dendrogram.cut_at_pairs(k),
+                    circuit_sizes(points.len(), &all[..k])
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_ties_broken_by_index() {
+        // Every neighbour on the square is at distance 1 and both diagonals
+        // at distance 2, so only the indices can order the pairs.
+        let points = [[0, 0], [1, 0], [1, 1], [0, 1]];
+        let metric = Metric::Manhattan;
+        assert_eq!(
+            closest_pairs(&points, metric, 4),
+            vec![(1, [0, 1]), (1, [0, 3]), (1, [1, 2]), (1, [2, 3])]
+        );
+        let merges = Dendrogram::build(&points, metric).merges().to_vec();
+        assert_eq!(
+            merges.iter().map(|m| m.pair).collect::<Vec<_>>(),
+            vec![[0, 1], [0, 3], [1, 2]]
+        );
+        // Under Chebyshev the diagonals tie with the sides as well.
+        assert_eq!(
+            closest_pairs(&points, Metric::Chebyshev, 3),
+            vec![(1, [0, 1]), (1, [0, 2]), (1, [0, 3])]
+        );
+        assert_eq!(
+            circuit_sizes(4, &closest_pairs(&points, Metric::Chebyshev, 2)),
+            vec![3, 1]
+        );
+    }
+}
@@ -0,0 +1,111 @@
+use std::fmt::Write;
+
+use crate::cluster::circuit_labels;
+
+/// Junction boxes with the connections made so far, ready to be written out
+/// for a 3D viewer. Points with fewer than three coordinates are padded with
+/// zeros and extra coordinates are dropped.
+pub struct Scene {
+    vertices: Vec<[i64; 3]>,
+    colors: Vec<[u8; 3]>,
+    connections: Vec<[usize; 2]>,
+}
+
+/// Spreads circuit colours around the hue wheel by the golden angle; boxes
+/// still on their own are grey.
+fn circuit_color(label: usize, size: usize) -> [u8; 3] {
+    if size == 1 {
+        return [128, 128, 128];
+    }
+    let h = (label as f64 * 137.507_764) % 360.0 / 60.0;
+    let x = 1.0 - (h % 2.0 - 1.0).abs();
+    let (r, g, b) = match h as u32 {
+        0 => (1.0, x, 0.0),
+        1 => (x, 1.0, 0.0),
+        2 => (0.0, 1.0, x),
+        3 => (0.0, x, 1.0),
+        4 => (x, 0.0, 1.0),
+        _ => (1.0, 0.0, x),
+    };
+    [r, g, b].map(|c| (55.0 + 200.0 * c) as u8)
+}
+
+impl Scene {
+    pub fn new<const N: usize>(points: &[[i64; N]], connections: Vec<[usize; 2]>) -> Self {
+        let labels = circuit_labels(points.len(), connections.iter().copied());
+        let mut sizes = vec![0; points.len()];
+        for &l in &labels {
+            sizes[l] += 1;
+        }
+        Self {
+            vertices: points
+                .iter()
+                .map(|p| std::array::from_fn(|k| p.get(k).copied().unwrap_or(0)))
+                .collect(),
+            colors: labels.iter().map(|&l| circuit_color(l, sizes[l])).collect(),
+            connections,
+        }
+    }
+
+    pub fn to_ply(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "ply").unwrap();
+        writeln!(out, "format ascii 1.0").unwrap();
+        writeln!(out, "element vertex {}", self.vertices.len()).unwrap();
+        for prop in ["float x", "float y", "float z"] {
+            writeln!(out, "property {}", prop).unwrap();
+        }
+        for prop in ["uchar red", "uchar green", "uchar blue"] {
+            writeln!(out, "property {}", prop).unwrap();
+        }
+        writeln!(out, "element edge {}", self.connections.len()).unwrap();
+        writeln!(out, "property int vertex1").unwrap();
+        writeln!(out, "property int vertex2").unwrap();
+        writeln!(out, "end_header").unwrap();
+        for ([x, y, z], [r, g, b]) in self.vertices.iter().zip(&self.colors) {
+            writeln!(out, "{x} {y} {z} {r} {g} {b}").unwrap();
+        }
+        for [i, j] in &self.connections {
+            writeln!(out, "{i} {j}").unwrap();
+        }
+        out
+    }
+
+    /// Wavefront OBJ with per-vertex colours as trailing `r g b` in [0, 1].
+    pub fn to_obj(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "# junction boxes coloured by circuit").unwrap();
+        for ([x, y, z], [r, g, b]) in self.vertices.iter().zip(&self.colors) {
+            let [r, g, b] = [r, g, b].map(|&c| c as f64 / 255.0);
+            writeln!(out, "v {x} {y} {z} {r:.3} {g:.3} {b:.3}").unwrap();
+        }
+        for [i, j] in &self.connections {
+            writeln!(out, "l {} {}", i + 1, j + 1).unwrap();
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export() {
+        let points = [[0, 0], [1, 0], [5, 5], [9, 9]];
+        let scene = Scene::new(&points, vec![[0, 1]]);
+        let ply = scene.to_ply();
+        assert!(ply.contains("element vertex 4\n"));
+        assert!(ply.contains("element edge 1\n"));
+        assert!(ply.ends_with("0 1\n"));
+        let lines = ply.lines().collect::<Vec<_>>();
+        let header = lines.iter().position(|&l| l == "end_header").unwrap();
+        assert!(lines[header + 1].starts_with("0 0 0 "));
+        assert_eq!(lines[header + 1][6..], lines[header + 2][6..]);
+        assert_eq!(lines[header + 3], "5 5 0 128 128 128");
+
+        let obj = scene.to_obj();
+        assert!(obj.contains("v 9 9 0 0.502 0.502 0.502\n"));
+        assert!(obj.ends_with("l 1 2\n"));
+    }
+}
@@ -1,10 +1,14 @@
-use std::{
-    collections::{HashMap, HashSet},
-    path::PathBuf,
-    time::Instant,
-};
+use std::{cell::OnceCell, path::PathBuf, time::Instant};
 
-fn parse_input(input: &str) -> Vec<[i64; 3]> {
+mod cluster;
+mod export;
+mod metric;
+
+use cluster::{Dendrogram, circuit_sizes, closest_pairs};
+use export::Scene;
+use metric::Metric;
+
+fn parse_input<const N: usize>(input: &str) -> Vec<[i64; N]> {
     input
         .trim()
         .lines()
@@ -13,108 +17,142 @@ fn parse_input(input: &str) -> Vec<[i64; 3]> {
                 .trim()
                 .split(',')
                 .map(|part| part.trim().parse().unwrap());
-            [
-                parts.next().unwrap(),
-                parts.next().unwrap(),
-                parts.next().unwrap(),
-            ]
+            std::array::from_fn(|_| parts.next().unwrap())
         })
         .collect()
 }
 
-fn sorted_pair_indices(points: &[[i64; 3]]) -> impl Iterator<Item = [usize; 2]> {
-    let mut indices = (0..points.len())
-        .flat_map(|i| (i + 1..points.len()).map(move |j| [i, j]))
-        .map(|[i, j]| {
-            (
-                [i, j],
-                (points[i][0] - points[j][0]).pow(2)
-                    + (points[i][1] - points[j][1]).pow(2)
-                    + (points[i][2] - points[j][2]).pow(2),
-            )
-        })
-        .collect::<Vec<_>>();
-    indices.sort_by_key(|(_, d)| *d);
-    indices.into_iter().map(|(ind, _)| ind)
+fn part1<const N: usize>(input: &str, n_junc: usize, metric: Metric) -> u64 {
+    let points = parse_input::<N>(input);
+    circuit_sizes(points.len(), &closest_pairs(&points, metric, n_junc))
+        .into_iter()
+        .take(3)
+        .map(|size| size as u64)
+        .product()
 }
 
-fn part1(input: &str, n_junc: usize) -> u64 {
-    let points = parse_input(input);
-    let mut circuits = HashMap::<usize, HashSet<usize>>::from_iter(
-        (0..points.len()).map(|i| (i, HashSet::from_iter([i]))),
-    );
-    let mut map_to_circuit = HashMap::<usize, usize>::from_iter((0..points.len()).map(|i| (i, i)));
-    let mut max_circ_id = points.len();
-    for [i, j] in sorted_pair_indices(&points).take(n_junc) {
-        let circ_i_id = *map_to_circuit.get(&i).unwrap();
-        let circ_j_id = *map_to_circuit.get(&j).unwrap();
-        if circ_i_id == circ_j_id {
-            continue;
-        }
-        let mut new_circ = circuits.remove(&circ_i_id).unwrap();
-        new_circ.extend(circuits.remove(&circ_j_id).unwrap());
-        for i in &new_circ {
-            map_to_circuit.insert(*i, max_circ_id);
-        }
-        circuits.insert(max_circ_id, new_circ);
-        max_circ_id += 1;
-    }
-    let mut circuit_size = circuits
-        .values()
-        .map(|circ| circ.len() as u64)
-        .collect::<Vec<u64>>();
-    circuit_size.sort();
-    circuit_size.reverse();
-    circuit_size.into_iter().take(3).product()
+fn part2<const N: usize>(input: &str, metric: Metric) -> u64 {
+    let points = parse_input::<N>(input);
+    let [i, j] = Dendrogram::build(&points, metric)
+        .merges()
+        .last()
+        .unwrap()
+        .pair;
+    (points[i][0] * points[j][0]) as u64
 }
 
-fn part2(input: &str) -> u64 {
-    let points = parse_input(input);
-    let mut circuits = HashMap::<usize, HashSet<usize>>::from_iter(
-        (0..points.len()).map(|i| (i, HashSet::from_iter([i]))),
-    );
-    let mut map_to_circuit = HashMap::<usize, usize>::from_iter((0..points.len()).map(|i| (i, i)));
-    let mut max_circ_id = points.len();
-    for [i, j] in sorted_pair_indices(&points) {
-        let circ_i_id = *map_to_circuit.get(&i).unwrap();
-        let circ_j_id = *map_to_circuit.get(&j).unwrap();
-        if circ_i_id == circ_j_id {
-            continue;
-        }
-        let mut new_circ = circuits.remove(&circ_i_id).unwrap();
-        new_circ.extend(circuits.remove(&circ_j_id).unwrap());
-        for i in &new_circ {
-            map_to_circuit.insert(*i, max_circ_id);
-        }
-        circuits.insert(max_circ_id, new_circ);
-        max_circ_id += 1;
-        if circuits.len() == 1 {
-            return (points[i][0] * points[j][0]) as u64;
+fn run<const N: usize>(input: &str, args: &[String]) {
+    let flag = |name: &str| {
+        args.iter()
+            .position(|a| a == name)
+            .and_then(|i| args.get(i + 1))
+    };
+    let metric = match args.iter().position(|a| a == "--metric") {
+        Some(i) => match args.get(i + 1).map(|m| m.parse()) {
+            Some(Ok(metric)) => metric,
+            Some(Err(e)) => {
+                eprintln!("{e}");
+                std::process::exit(1);
+            }
+            None => {
+                eprintln!("--metric needs euclidean, manhattan or chebyshev");
+                std::process::exit(1);
+            }
+        },
+        None => Metric::default(),
+    };
+    let n_junc = 1000;
+    let t = Instant::now();
+    println!("Part 1: {}", part1::<N>(input, n_junc, metric));
+    println!("Part 1 took: {:?}", t.elapsed());
+    let t = Instant::now();
+    println!("Part 2: {}", part2::<N>(input, metric));
+    println!("Part 2 took: {:?}", t.elapsed());
+    let points = parse_input::<N>(input);
+    // Only built when one of the flags below needs it.
+    let tree = OnceCell::new();
+    let dendrogram = || tree.get_or_init(|| Dendrogram::build(&points, metric));
+    if args.iter().any(|a| a == "--mst") {
+        let label = match metric {
+            Metric::Euclidean => "dist²",
+            _ => "dist",
+        };
+        for (k, merge) in dendrogram().merges().iter().enumerate() {
+            println!(
+                "{:?} -> {:?}  {}={}  circuits {} + {} -> {} (size {})",
+                points[merge.pair[0]],
+                points[merge.pair[1]],
+                label,
+                merge.dist,
+                merge.clusters[0],
+                merge.clusters[1],
+                points.len() + k,
+                merge.size
+            );
         }
     }
-    unreachable!()
+    if let Some(n_pairs) = flag("--cut-pairs") {
+        let sizes = dendrogram().cut_at_pairs(n_pairs.parse().unwrap());
+        println!("Circuits after {} pairs: {:?}", n_pairs, sizes);
+    }
+    if let Some(max_dist) = flag("--cut-dist") {
+        let sizes = dendrogram().cut_at_distance(max_dist.parse().unwrap());
+        println!("Circuits within dist {}: {:?}", max_dist, sizes);
+    }
+    if let Some(path) = flag("--export") {
+        let connections = match flag("--step") {
+            Some(step) => dendrogram()
+                .merges()
+                .iter()
+                .take(step.parse().unwrap())
+                .map(|merge| merge.pair)
+                .collect(),
+            None => closest_pairs(&points, metric, n_junc)
+                .into_iter()
+                .map(|(_, pair)| pair)
+                .collect(),
+        };
+        let scene = Scene::new(&points, connections);
+        let out = if path.ends_with(".obj") {
+            scene.to_obj()
+        } else {
+            scene.to_ply()
+        };
+        std::fs::write(path, out).unwrap();
+        println!("Scene written to {}", path);
+    }
 }
 
 fn main() {
+    let args: Vec<String> = std::env::args().collect();
     let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
         .parent()
         .unwrap()
         .join("data")
         .join("day08.dat");
     let input = std::fs::read_to_string(path).unwrap();
-    let t = Instant::now();
-    println!("Part 1: {}", part1(&input, 1000));
-    println!("Part 1 took: {:?}", t.elapsed());
-    let t = Instant::now();
-    println!("Part 2: {}", part2(&input));
-    println!("Part 2 took: {:?}", t.elapsed());
+    let dim = input
+        .trim()
+        .lines()
+        .next()
+        .map_or(3, |l| l.split(',').count());
+    match dim {
+        1 => run::<1>(&input, &args),
+        2 => run::<2>(&input, &args),
+        3 => run::<3>(&input, &args),
+        4 => run::<4>(&input, &args),
+        _ => {
+            eprintln!("unsupported point dimension {dim}, expected 1 to 4");
+            std::process::exit(1);
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const EXAMPLE: &str = r#"162,817,812
+    pub const EXAMPLE: &str = r#"162,817,812
 57,618,57
 906,360,560
 592,479,940
@@ -137,11 +175,11 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(EXAMPLE, 10), 40);
+        assert_eq!(part1::<3>(EXAMPLE, 10, Metric::Euclidean), 40);
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(EXAMPLE), 25272);
+        assert_eq!(part2::<3>(EXAMPLE, Metric::Euclidean), 25272);
     }
 }
@@ -0,0 +1,52 @@
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Metric {
+    /// Straight-line distance, kept squared so that it stays an exact integer.
+    #[default]
+    Euclidean,
+    Manhattan,
+    Chebyshev,
+}
+
+impl Metric {
+    pub fn dist<const N: usize>(self, a: &[i64; N], b: &[i64; N]) -> i64 {
+        let deltas = a.iter().zip(b).map(|(x, y)| (x - y).abs());
+        match self {
+            Metric::Euclidean => deltas.map(|d| d * d).sum(),
+            Metric::Manhattan => deltas.sum(),
+            Metric::Chebyshev => deltas.max().unwrap_or(0),
+        }
+    }
+}
+
+impl FromStr for Metric {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "euclidean" => Ok(Metric::Euclidean),
+            "manhattan" => Ok(Metric::Manhattan),
+            "chebyshev" => Ok(Metric::Chebyshev),
+            _ => Err(format!(
+                "unknown metric `{s}`, expected euclidean, manhattan or chebyshev"
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metrics() {
+        let (a, b) = ([0, 0, 0], [3, -4, 1]);
+        assert_eq!(Metric::Euclidean.dist(&a, &b), 26);
+        assert_eq!(Metric::Manhattan.dist(&a, &b), 8);
+        assert_eq!(Metric::Chebyshev.dist(&a, &b), 4);
+        assert_eq!(Metric::Manhattan.dist(&[1, 2], &[4, 0]), 5);
+        assert_eq!("Chebyshev".parse(), Ok(Metric::Chebyshev));
+        assert!("cosine".parse::<Metric>().is_err());
+    }
+}
@@ -0,0 +1,231 @@
+use std::collections::VecDeque;
+
+/// Splits one axis into blocks of tiles: one block per polygon coordinate,
+/// one for each non-empty gap between consecutive coordinates, and a padding
+/// block on either side.
+struct Axis {
+    /// Inclusive tile range covered by each block.
+    blocks: Vec<(i64, i64)>,
+}
+
+impl Axis {
+    fn new(coords: impl Iterator<Item = i64>) -> Self {
+        let mut coords = coords.collect::<Vec<_>>();
+        coords.sort_unstable();
+        coords.dedup();
+        let mut blocks = vec![(coords[0] - 1, coords[0] - 1)];
+        for (k, &c) in coords.iter().enumerate() {
+            blocks.push((c, c));
+            match coords.get(k + 1) {
+                Some(&next) if next > c + 1 => blocks.push((c + 1, next - 1)),
+                Some(_) => {}
+                None => blocks.push((c + 1, c + 1)),
+            }
+        }
+        Self { blocks }
+    }
+
+    fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// The block holding tile `c`. Tiles past either end of the axis share
+    /// the padding block on that side, which is outside the loop like them.
+    fn index(&self, c: i64) -> usize {
+        self.blocks
+            .partition_point(|&(start, _)| start <= c)
+            .saturating_sub(1)
+    }
+}
+
+/// The tiles enclosed by a closed loop of red tiles, rasterised once on the
+/// compressed grid with a 2D prefix sum of the outside blocks.
+pub struct CompressedGrid {
+    i_axis: Axis,
+    j_axis: Axis,
+    /// `outside[a][b]` counts the outside blocks in `[0, a) × [0, b)`.
+    outside: Vec<Vec<u32>>,
+    /// The block holding each vertex, in the order they were given.
+    vertex_blocks: Vec<[usize; 2]>,
+}
+
+impl CompressedGrid {
+    pub fn new(vertices: &[[i64; 2]]) -> Self {
+        let i_axis = Axis::new(vertices.iter().map(|p| p[0]));
+        let j_axis = Axis::new(vertices.iter().map(|p| p[1]));
+        let (ni, nj) = (i_axis.len(), j_axis.len());
+        let vertex_blocks = vertices
+            .iter()
+            .map(|p| [i_axis.index(p[0]), j_axis.index(p[1])])
+            .collect::<Vec<_>>();
+
+        let mut boundary = vec![vec![false; nj]; ni];
+        for (k, &[a1, b1]) in vertex_blocks.iter().enumerate() {
+            let [a2, b2] = vertex_blocks[(k + 1) % vertex_blocks.len()];
+            for row in &mut boundary[a1.min(a2)..=a1.max(a2)] {
+                row[b1.min(b2)..=b1.max(b2)].fill(true);
+            }
+        }
+
+        // Flood fill from the padding ring; whatever it can't reach is inside.
+        let mut is_outside = vec![vec![false; nj]; ni];
+        let mut queue = VecDeque::from([(0usize, 0usize)]);
+        is_outside[0][0] = true;
+        while let Some((a, b)) = queue.pop_front() {
+            let neighbours = [
+                (a.wrapping_sub(1), b),
+                (a + 1, b),
+                (a, b.wrapping_sub(1)),
+                (a, b + 1),
+            ];
+            for (na, nb) in neighbours {
+                if na < ni && nb < nj && !boundary[na][nb] && !is_outside[na][nb] {
+                    is_outside[na][nb] = true;
+                    queue.push_back((na, nb));
+                }
+            }
+        }
+
+        let mut outside = vec![vec![0; nj + 1]; ni + 1];
+        for a in 0..ni {
+            for b in 0..nj {
+                outside[a + 1][b + 1] =
+                    outside[a][b + 1] + outside[a + 1][b] - outside[a][b] + is_outside[a][b] as u32;
+            }
+        }
+        Self {
+            i_axis,
+            j_axis,
+            outside,
+            vertex_blocks,
+        }
+    }
+
+    /// Whether every tile of the rectangle spanned by two corners lies inside
+    /// the loop or on it, in O(log n) to find the corners' blocks.
+    #[cfg(test)]
+    pub fn contains_rect(&self, p1: [i64; 2], p2: [i64; 2]) -> bool {
+        let block = |p: [i64; 2]| [self.i_axis.index(p[0]), self.j_axis.index(p[1])];
+        self.contains_blocks(block(p1), block(p2))
+    }
+
+    /// Like `contains_rect` for the rectangle spanned by the `k1`-th and
+    /// `k2`-th vertices, whose blocks are already known, in O(1).
+    pub fn contains_vertex_rect(&self, k1: usize, k2: usize) -> bool {
+        self.contains_blocks(self.vertex_blocks[k1], self.vertex_blocks[k2])
+    }
+
+    fn contains_blocks(&self, [a1, b1]: [usize; 2], [a2, b2]: [usize; 2]) -> bool {
+        let (a1, a2) = (a1.min(a2), a1.max(a2) + 1);
+        let (b1, b2) = (b1.min(b2), b1.max(b2) + 1);
+        self.outside[a2][b2] + self.outside[a1][b1] == self.outside[a1][b2] + self.outside[a2][b1]
+    }
+
+    fn is_inside(&self, a: usize, b: usize) -> bool {
+        self.outside[a + 1][b + 1] + self.outside[a][b]
+            == self.outside[a][b + 1] + self.outside[a + 1][b]
+    }
+
+    /// Opposite corners of the largest rectangle of tiles inside the loop or
+    /// on it, with no restriction on the corners. Every tile of a block is
+    /// alike, so the best rectangle follows block edges and a largest
+    /// rectangle in a histogram with weighted columns per block row finds it.
+    pub fn largest_inside_rect(&self) -> Option<[[i64; 2]; 2]> {
+        let (ni, nj) = (self.i_axis.len(), self.j_axis.len());
+        let mut heights = vec![0; nj];
+        let mut best: Option<(u64, [[i64; 2]; 2])> = None;
+        for a in 0..ni {
+            let (i_start, i_end) = self.i_axis.blocks[a];
+            for (b, h) in heights.iter_mut().enumerate() {
+                *h = if self.is_inside(a, b) {
+                    *h + i_end - i_start + 1
+                } else {
+                    0
+                };
+            }
+            // Columns on the stack have increasing heights; each one is the
+            // leftmost column its height reaches back to.
+            let mut stack: Vec<(usize, i64)> = Vec::new();
+            for b in 0..=nj {
+                let h = heights.get(b).copied().unwrap_or(0);
+                let mut left = b;
+                while let Some(&(l, top)) = stack.last().filter(|&&(_, top)| top >= h) {
+                    stack.pop();
+                    left = l;
+                    let (j_start, j_end) = (self.j_axis.blocks[l].0, self.j_axis.blocks[b - 1].1);
+                    let area = (top * (j_end - j_start + 1)) as u64;
+                    if top > 0 && best.is_none_or(|(max, _)| area > max) {
+                        best = Some((area, [[i_end - top + 1, j_start], [i_end, j_end]]));
+                    }
+                }
+                stack.push((left, h));
+            }
+        }
+        best.map(|(_, corners)| corners)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contains_rect() {
+        // An L-shaped loop with a notch in the top right corner.
+        let grid = CompressedGrid::new(&[[0, 0], [0, 10], [4, 10], [4, 5], [10, 5], [10, 0]]);
+        assert!(grid.contains_rect([0, 0], [10, 5]));
+        assert!(grid.contains_rect([0, 10], [4, 0]));
+        assert!(grid.contains_rect([2, 2], [3, 9]));
+        assert!(!grid.contains_rect([0, 0], [10, 10]));
+        assert!(!grid.contains_rect([4, 10], [10, 5]));
+        assert!(!grid.contains_rect([5, 6], [5, 6]));
+        assert!(grid.contains_rect([5, 5], [5, 5]));
+        assert!(grid.contains_vertex_rect(0, 4));
+        assert!(!grid.contains_vertex_rect(1, 4));
+        assert_eq!(grid.largest_inside_rect(), Some([[0, 0], [10, 5]]));
+    }
+
+    #[test]
+    fn test_contains_rect_off_grid() {
+        let grid = CompressedGrid::new(&[[5, 5], [5, 10], [10, 10], [10, 5]]);
+        assert!(grid.contains_rect([6, 6], [9, 9]));
+        assert!(!grid.contains_rect([0, 0], [6, 6]));
+        assert!(!grid.contains_rect([-100, 7], [7, 8]));
+        assert!(!grid.contains_rect([7, 7], [20, 8]));
+        assert!(!grid.contains_rect([0, 0], [2, 2]));
+        assert!(!grid.contains_rect([i64::MIN, i64::MIN], [i64::MAX, i64::MAX]));
+    }
+
+    #[test]
+    fn test_largest_inside_rect_matches_brute_force() {
+        // A plus sign: the arms are 3 wide and 9 long, so no red corners
+        // bound the largest rectangle.
+        let vertices = [
+            [0, 3],
+            [0, 5],
+            [3, 5],
+            [3, 8],
+            [5, 8],
+            [5, 5],
+            [8, 5],
+            [8, 3],
+            [5, 3],
+            [5, 0],
+            [3, 0],
+            [3, 3],
+        ];
+        let grid = CompressedGrid::new(&vertices);
+        let mut best = 0;
+        for (i1, j1) in (0..=8).flat_map(|i| (0..=8).map(move |j| (i, j))) {
+            for (i2, j2) in (i1..=8).flat_map(|i| (j1..=8).map(move |j| (i, j))) {
+                if grid.contains_rect([i1, j1], [i2, j2]) {
+                    best = best.max((i2 - i1 + 1) * (j2 - j1 + 1));
+                }
+            }
+        }
+        let [p1, p2] = grid.largest_inside_rect().unwrap();
+        assert!(grid.contains_rect(p1, p2));
+        assert_eq!((p2[0] - p1[0] + 1) * (p2[1] - p1[1] + 1), best);
+        assert_eq!(best, 27);
+    }
+}
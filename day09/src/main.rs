@@ -1,5 +1,13 @@
 use std::{path::PathBuf, time::Instant};
 
+mod compressed;
+mod polygon;
+mod rects;
+mod svg;
+
+use polygon::RectilinearPolygon;
+use rects::{BestRectangle, RectQuery};
+
 fn get_red_tile_pos(input: &str) -> impl Iterator<Item = [i64; 2]> + '_ {
     input.trim().lines().map(|line| {
         let (i_str, j_str) = line.split_once(',').unwrap();
@@ -9,147 +17,22 @@ fn get_red_tile_pos(input: &str) -> impl Iterator<Item = [i64; 2]> + '_ {
     })
 }
 
-fn part1(input: &str) -> u64 {
+fn part1(input: &str) -> Option<BestRectangle> {
     let pos = get_red_tile_pos(input).collect::<Vec<_>>();
-    pos.iter()
-        .enumerate()
-        .flat_map(|(i, p1)| {
-            pos[i + 1..].iter().map(move |p2| {
-                (
-                    p1,
-                    p2,
-                    ((p2[0] - p1[0]).unsigned_abs() + 1) * ((p2[1] - p1[1]).unsigned_abs() + 1),
-                )
-            })
-        })
-        .max_by_key(|&(_, _, area)| area)
-        .inspect(|(p1, p2, _)| {
-            println!("max between {:?} and {:?}", p1, p2);
-        })
-        .unwrap()
-        .2
+    RectQuery::unconstrained(&pos).largest()
 }
 
-#[cfg(test)]
-fn printgrid(pos: &[[i64; 2]], p1: &[i64; 2], p2: &[i64; 2]) {
-    let i_max = pos.iter().map(|p| p[0]).max().unwrap();
-    let j_max = pos.iter().map(|p| p[1]).max().unwrap();
-    let mut grid = vec![vec!['.'; (j_max + 2) as usize]; (i_max + 2) as usize];
-    for pslice in pos
-        .windows(2)
-        .chain([pos[pos.len() - 1], pos[0]].windows(2))
-    {
-        let p1 = pslice[0];
-        let p2 = pslice[1];
-        grid[p1[0] as usize][p1[1] as usize] = '█';
-        if p1[0] == p2[0] {
-            for j in p1[1].min(p2[1]) + 1..=p1[1].max(p2[1]) {
-                grid[p1[0] as usize][j as usize] = '█';
-            }
-        } else {
-            for i in p1[0].min(p2[0]) + 1..=p1[0].max(p2[0]) {
-                grid[i as usize][p1[1] as usize] = '█';
-            }
-        }
-    }
-    grid[p1[0] as usize][p1[1] as usize] = 'X';
-    grid[p2[0] as usize][p2[1] as usize] = 'X';
-    for row in grid {
-        for c in row {
-            print!("{}", c);
-        }
-        println!();
-    }
+/// `None` if there is no rectangle or the red tiles don't form a valid loop.
+fn part2(input: &str) -> Option<BestRectangle> {
+    let polygon = RectilinearPolygon::new(get_red_tile_pos(input).collect()).ok()?;
+    RectQuery::in_polygon(&polygon).largest()
 }
 
-fn part2(input: &str) -> u64 {
-    let pos = get_red_tile_pos(input).collect::<Vec<_>>();
-    let mut j_lines = vec![];
-    let mut i_lines = vec![];
-    for pos_slice in pos
-        .windows(2)
-        .chain([pos[pos.len() - 1], pos[0]].windows(2))
-    {
-        let p1 = pos_slice[0];
-        let p2 = pos_slice[1];
-        if p1[0] == p2[0] {
-            if p1[1] > p2[1] {
-                i_lines.push([p2, p1]);
-            } else {
-                i_lines.push([p1, p2]);
-            }
-        } else if p1[0] > p2[0] {
-            j_lines.push([p2, p1]);
-        } else {
-            j_lines.push([p1, p2]);
-        }
-    }
-    j_lines.sort_by_key(|line| line[0][0]);
-    i_lines.sort_by_key(|line| line[0][1]);
-    pos.iter()
-        .enumerate()
-        .flat_map(|(i, p1)| pos[i + 1..].iter().map(move |p2| [p1, p2]))
-        .filter_map(|[p1, p2]| {
-            let (i_min, i_max) = if p1[0] < p2[0] {
-                (p1[0], p2[0])
-            } else {
-                (p2[0], p1[0])
-            };
-            let (j_min, j_max) = if p1[1] < p2[1] {
-                (p1[1], p2[1])
-            } else {
-                (p2[1], p1[1])
-            };
-            let rect_i_range_excl = i_min + 1..=i_max - 1;
-            let rect_i_range_incl = i_min..=i_max;
-            let rect_j_range_excl = j_min + 1..=j_max - 1;
-            let rect_j_range_incl = j_min..=j_max;
-            // check if rect edges intersect any poly lines
-            if j_lines.iter().any(|j_line| {
-                let line_i_range = j_line[0][0] + 1..j_line[1][0];
-                rect_j_range_excl.contains(&j_line[0][1]) && {
-                    line_i_range.contains(&i_min)
-                        || line_i_range.contains(&i_max)
-                        || (j_line[0][0] == i_min && rect_i_range_incl.contains(&j_line[1][0]))
-                        || (j_line[1][0] == i_max && rect_i_range_incl.contains(&j_line[0][0]))
-                }
-            }) || i_lines.iter().any(|i_line| {
-                let line_j_range = i_line[0][1] + 1..i_line[1][1];
-                rect_i_range_excl.contains(&i_line[0][0]) && {
-                    line_j_range.contains(&j_min)
-                        || line_j_range.contains(&j_max)
-                        || (i_line[0][1] == j_min && rect_j_range_incl.contains(&i_line[1][1]))
-                        || (i_line[1][1] == j_max && rect_j_range_incl.contains(&i_line[0][1]))
-                }
-            }) {
-                None?
-            }
-            // check if mid point is inside
-            let mid_point = [(i_min + i_max) / 2, (j_min + j_max) / 2];
-            if j_lines
-                .iter()
-                .filter(|line| line[0][1] > mid_point[1])
-                .filter(|line| (line[0][0]..line[1][0]).contains(&mid_point[0]))
-                .count()
-                % 2
-                == 0
-            {
-                None?
-            }
-            Some((
-                p1,
-                p2,
-                ((p2[0] - p1[0]).unsigned_abs() + 1) * ((p2[1] - p1[1]).unsigned_abs() + 1),
-            ))
-        })
-        .max_by_key(|&(_, _, area)| area)
-        .inspect(|(p1, p2, _)| {
-            println!("max between {:?} and {:?}", p1, p2);
-            #[cfg(test)]
-            printgrid(&pos, p1, p2)
-        })
-        .unwrap()
-        .2
+/// Like part 2, but the corners may be any tiles rather than red ones.
+fn part2_any_corners(input: &str) -> Option<BestRectangle> {
+    let polygon = RectilinearPolygon::new(get_red_tile_pos(input).collect()).ok()?;
+    let [p1, p2] = polygon.largest_inscribed_rect()?;
+    Some(BestRectangle::new(p1, p2))
 }
 
 fn main() {
@@ -159,17 +42,132 @@ fn main() {
         .join("data")
         .join("day09.dat");
     let input = std::fs::read_to_string(path).unwrap();
+    let args: Vec<String> = std::env::args().collect();
+    let flag = |name: &str| {
+        args.iter()
+            .position(|a| a == name)
+            .and_then(|i| args.get(i + 1))
+    };
+    let load_polygon = || match RectilinearPolygon::new(get_red_tile_pos(&input).collect()) {
+        Ok(polygon) => polygon,
+        Err(e) => {
+            eprintln!("invalid polygon: {e}");
+            std::process::exit(1);
+        }
+    };
+    if args.iter().any(|a| a == "--polygon") {
+        let polygon = load_polygon();
+        println!("Vertices: {}", polygon.vertices().len());
+        println!("Orientation: {:?}", polygon.orientation());
+        println!("Area: {}", polygon.area());
+        println!("Boundary tiles: {}", polygon.boundary_tiles());
+        println!("Tiles: {}", polygon.tile_count());
+        if let Some(p) = flag("--locate") {
+            let p = get_red_tile_pos(p).next().unwrap();
+            println!("{p:?} is {:?}", polygon.locate(p));
+        }
+        return;
+    }
+    if let Some(out) = flag("--svg") {
+        let polygon = load_polygon();
+        let highlights = [
+            ("part 1", "#1565c0", part1(&input)),
+            ("part 2", "#f9a825", part2(&input)),
+            ("any corners", "#6a1b9a", part2_any_corners(&input)),
+        ]
+        .into_iter()
+        .filter_map(|(label, color, best)| {
+            best.map(|r| svg::Highlight {
+                label,
+                color,
+                corners: [r.corner_a, r.corner_b],
+            })
+        })
+        .collect::<Vec<_>>();
+        std::fs::write(out, svg::render(&polygon, &highlights)).unwrap();
+        println!("Wrote {out}");
+        return;
+    }
+    let (top, above, bucket) = (flag("--top"), flag("--above"), flag("--histogram"));
+    if top.is_some() || above.is_some() || bucket.is_some() {
+        if bucket.is_some_and(|w| w.parse() == Ok(0u64)) {
+            eprintln!("--histogram needs a bucket width of at least 1");
+            std::process::exit(1);
+        }
+        let pos = get_red_tile_pos(&input).collect::<Vec<_>>();
+        let polygon = load_polygon();
+        let queries = [
+            (1, RectQuery::unconstrained(&pos)),
+            (2, RectQuery::in_polygon(&polygon)),
+        ];
+        for (part, query) in queries {
+            if let Some(n) = top {
+                for (rank, r) in query.top(n.parse().unwrap()).iter().enumerate() {
+                    let (a, b) = (r.corner_a, r.corner_b);
+                    println!(
+                        "Part {part} #{}: {} between {a:?} and {b:?}",
+                        rank + 1,
+                        r.area
+                    );
+                }
+            }
+            if let Some(min_area) = above {
+                let count = query.count_above(min_area.parse().unwrap());
+                println!("Part {part}: {count} rectangles above {min_area}");
+            }
+            if let Some(width) = bucket {
+                let width = width.parse().unwrap();
+                for (start, count) in query.area_histogram(width) {
+                    println!("Part {part}: [{start}, {}) {count}", start + width);
+                }
+            }
+        }
+        return;
+    }
+    let verbose = args.iter().any(|a| a == "-v" || a == "--verbose");
+    let report = |label: &str, best: Option<BestRectangle>| match best {
+        Some(r) if verbose => println!(
+            "{label}: {} (between {:?} and {:?})",
+            r.area, r.corner_a, r.corner_b
+        ),
+        Some(r) => println!("{label}: {}", r.area),
+        None => println!("{label}: no rectangle"),
+    };
+    // Part 2 also comes back empty for red tiles that don't form a valid
+    // loop, so say why.
+    let report_in_polygon = |label: &str, best: Option<BestRectangle>| {
+        let error = best
+            .is_none()
+            .then(|| RectilinearPolygon::new(get_red_tile_pos(&input).collect()).err())
+            .flatten();
+        match error {
+            Some(e) => println!("{label}: invalid polygon: {e}"),
+            None => report(label, best),
+        }
+    };
     let t = Instant::now();
-    println!("Part 1: {}", part1(&input));
-    println!("Part 1 took: {:?}", t.elapsed());
+    let best = part1(&input);
+    let elapsed = t.elapsed();
+    report("Part 1", best);
+    println!("Part 1 took: {:?}", elapsed);
     let t = Instant::now();
-    println!("Part 2: {}", part2(&input));
-    println!("Part 2 took: {:?}", t.elapsed());
+    let best = part2(&input);
+    let elapsed = t.elapsed();
+    report_in_polygon("Part 2", best);
+    println!("Part 2 took: {:?}", elapsed);
+    if args.iter().any(|a| a == "--any-corners") {
+        let t = Instant::now();
+        let best = part2_any_corners(&input);
+        let elapsed = t.elapsed();
+        report_in_polygon("Part 2 (any corners)", best);
+        println!("Part 2 (any corners) took: {:?}", elapsed);
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+
     fn flipv(input: &str) -> String {
         let mut out = String::with_capacity(input.len());
         for line in input.trim().lines().rev() {
@@ -199,7 +197,7 @@ mod tests {
         out
     }
 
-    const EXAMPLE: &str = r#"7,1
+    pub const EXAMPLE: &str = r#"7,1
 11,1
 11,7
 9,7
@@ -210,16 +208,42 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(EXAMPLE), 50);
+        assert_eq!(
+            part1(EXAMPLE),
+            Some(BestRectangle {
+                corner_a: [11, 1],
+                corner_b: [2, 5],
+                area: 50,
+            })
+        );
+        assert_eq!(part1("3,4"), None);
+    }
+
+    #[test]
+    fn test_part2_any_corners() {
+        // Stretching the part 2 rectangle down to row 11 ends on green tiles.
+        assert_eq!(part2_any_corners(EXAMPLE).unwrap().area, 30);
+        let best = part2_any_corners(&flip_ij(EXAMPLE)).unwrap();
+        assert_eq!((best.corner_a, best.corner_b), ([3, 2], [5, 11]));
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(EXAMPLE), 24);
-        assert_eq!(part2(&flipv(EXAMPLE)), 24);
-        assert_eq!(part2(&fliph(EXAMPLE)), 24);
-        assert_eq!(part2(&flip_ij(EXAMPLE)), 24);
-        assert_eq!(part2(&flip_ij(&flipv(EXAMPLE))), 24);
-        assert_eq!(part2(&flip_ij(&fliph(EXAMPLE))), 24);
+        assert_eq!(
+            part2(EXAMPLE),
+            Some(BestRectangle {
+                corner_a: [9, 5],
+                corner_b: [2, 3],
+                area: 24,
+            })
+        );
+        assert_eq!(part2(&flipv(EXAMPLE)).unwrap().area, 24);
+        assert_eq!(part2(&fliph(EXAMPLE)).unwrap().area, 24);
+        assert_eq!(part2(&flip_ij(EXAMPLE)).unwrap().area, 24);
+        assert_eq!(part2(&flip_ij(&flipv(EXAMPLE))).unwrap().area, 24);
+        assert_eq!(part2(&flip_ij(&fliph(EXAMPLE))).unwrap().area, 24);
+        // A diagonal edge makes the red tiles an invalid loop.
+        assert_eq!(part2("0,0\n0,2\n2,3\n2,0"), None);
+        assert_eq!(part2_any_corners("0,0\n0,2\n2,3\n2,0"), None);
     }
 }
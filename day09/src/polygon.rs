@@ -0,0 +1,225 @@
+use std::fmt;
+
+use crate::compressed::CompressedGrid;
+
+#[derive(Debug, PartialEq)]
+pub enum PolygonError {
+    TooFewVertices(usize),
+    /// The edge starting at this vertex is neither horizontal nor vertical.
+    NotAxisAligned(usize),
+    /// The edge starting at this vertex has zero length.
+    RepeatedVertex(usize),
+    /// The edges starting at these two vertices cross or overlap.
+    SelfIntersection(usize, usize),
+}
+
+impl fmt::Display for PolygonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PolygonError::TooFewVertices(n) => write!(f, "a polygon needs 4 vertices, got {n}"),
+            PolygonError::NotAxisAligned(k) => write!(f, "edge {k} is not axis-aligned"),
+            PolygonError::RepeatedVertex(k) => write!(f, "vertex {k} is repeated"),
+            PolygonError::SelfIntersection(k, l) => write!(f, "edges {k} and {l} intersect"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Orientation {
+    Clockwise,
+    CounterClockwise,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Location {
+    Inside,
+    Boundary,
+    Outside,
+}
+
+/// Closed loop of red tiles joined by horizontal and vertical runs of tiles.
+pub struct RectilinearPolygon {
+    vertices: Vec<[i64; 2]>,
+    grid: CompressedGrid,
+}
+
+/// Inclusive bounding box of an edge, as `[[i_min, j_min], [i_max, j_max]]`.
+fn bbox([p1, p2]: [[i64; 2]; 2]) -> [[i64; 2]; 2] {
+    [
+        [p1[0].min(p2[0]), p1[1].min(p2[1])],
+        [p1[0].max(p2[0]), p1[1].max(p2[1])],
+    ]
+}
+
+impl RectilinearPolygon {
+    pub fn new(vertices: Vec<[i64; 2]>) -> Result<Self, PolygonError> {
+        let n = vertices.len();
+        if n < 4 {
+            return Err(PolygonError::TooFewVertices(n));
+        }
+        let edge = |k: usize| [vertices[k], vertices[(k + 1) % n]];
+        for k in 0..n {
+            let [p1, p2] = edge(k);
+            if p1 == p2 {
+                return Err(PolygonError::RepeatedVertex(k));
+            }
+            if p1[0] != p2[0] && p1[1] != p2[1] {
+                return Err(PolygonError::NotAxisAligned(k));
+            }
+        }
+        // Neighbouring edges may only share their common vertex, any other
+        // pair of edges must be disjoint.
+        for k in 0..n {
+            for l in k + 1..n {
+                let ([lo1, hi1], [lo2, hi2]) = (bbox(edge(k)), bbox(edge(l)));
+                let lo = [lo1[0].max(lo2[0]), lo1[1].max(lo2[1])];
+                let hi = [hi1[0].min(hi2[0]), hi1[1].min(hi2[1])];
+                if lo[0] > hi[0] || lo[1] > hi[1] {
+                    continue;
+                }
+                let adjacent = l == k + 1 || (k == 0 && l == n - 1);
+                if !adjacent || lo != hi {
+                    return Err(PolygonError::SelfIntersection(k, l));
+                }
+            }
+        }
+        let grid = CompressedGrid::new(&vertices);
+        Ok(Self { vertices, grid })
+    }
+
+    pub fn vertices(&self) -> &[[i64; 2]] {
+        &self.vertices
+    }
+
+    pub fn edges(&self) -> impl Iterator<Item = [[i64; 2]; 2]> + '_ {
+        let n = self.vertices.len();
+        (0..n).map(move |k| [self.vertices[k], self.vertices[(k + 1) % n]])
+    }
+
+    /// Twice the signed shoelace area of the loop through the tile centres.
+    fn signed_area2(&self) -> i64 {
+        self.edges()
+            .map(|[p1, p2]| p1[0] * p2[1] - p2[0] * p1[1])
+            .sum()
+    }
+
+    /// Orientation with `i` as the first axis and `j` as the second.
+    pub fn orientation(&self) -> Orientation {
+        if self.signed_area2() > 0 {
+            Orientation::CounterClockwise
+        } else {
+            Orientation::Clockwise
+        }
+    }
+
+    /// Area enclosed by the loop through the tile centres.
+    pub fn area(&self) -> i64 {
+        self.signed_area2().abs() / 2
+    }
+
+    /// Number of tiles on the loop itself.
+    pub fn boundary_tiles(&self) -> i64 {
+        self.edges()
+            .map(|[p1, p2]| (p1[0] - p2[0]).abs() + (p1[1] - p2[1]).abs())
+            .sum()
+    }
+
+    /// Number of tiles on or inside the loop, by Pick's theorem.
+    pub fn tile_count(&self) -> i64 {
+        self.area() + self.boundary_tiles() / 2 + 1
+    }
+
+    pub fn locate(&self, p: [i64; 2]) -> Location {
+        let on_edge = |e: [[i64; 2]; 2]| {
+            let [lo, hi] = bbox(e);
+            (lo[0]..=hi[0]).contains(&p[0]) && (lo[1]..=hi[1]).contains(&p[1])
+        };
+        if self.edges().any(on_edge) {
+            return Location::Boundary;
+        }
+        // Cast a ray towards increasing `j`, counting the runs of constant `j`
+        // it crosses with a half-open span so that vertices count once.
+        let crossings = self
+            .edges()
+            .filter(|[p1, p2]| p1[1] == p2[1] && p1[1] > p[1])
+            .filter(|[p1, p2]| (p1[0].min(p2[0])..p1[0].max(p2[0])).contains(&p[0]))
+            .count();
+        if crossings % 2 == 1 {
+            Location::Inside
+        } else {
+            Location::Outside
+        }
+    }
+
+    /// Whether the axis-aligned rectangle spanned by two vertices, given by
+    /// their index in `vertices`, only covers tiles on or inside the loop.
+    pub fn contains_vertex_rect(&self, k1: usize, k2: usize) -> bool {
+        self.grid.contains_vertex_rect(k1, k2)
+    }
+
+    /// Opposite corners of the largest rectangle that fits on or inside the
+    /// loop, whether or not its corners are red tiles.
+    pub fn largest_inscribed_rect(&self) -> Option<[[i64; 2]; 2]> {
+        self.grid.largest_inside_rect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{get_red_tile_pos, tests::EXAMPLE};
+
+    #[test]
+    fn test_polygon_measures() {
+        let polygon = RectilinearPolygon::new(get_red_tile_pos(EXAMPLE).collect()).unwrap();
+        assert_eq!(polygon.orientation(), Orientation::CounterClockwise);
+        assert_eq!(polygon.area(), 30);
+        assert_eq!(polygon.boundary_tiles(), 30);
+        assert_eq!(polygon.tile_count(), 46);
+        let tiles = (0..=12)
+            .flat_map(|i| (0..=8).map(move |j| [i, j]))
+            .filter(|&p| polygon.locate(p) != Location::Outside)
+            .count();
+        assert_eq!(tiles, 46);
+        assert_eq!(polygon.locate([7, 1]), Location::Boundary);
+        assert_eq!(polygon.locate([9, 2]), Location::Inside);
+        assert_eq!(polygon.locate([3, 2]), Location::Outside);
+        // Vertices 4 and 6 are [9, 5] and [2, 3], vertex 3 is [9, 7].
+        assert!(polygon.contains_vertex_rect(4, 6));
+        assert!(!polygon.contains_vertex_rect(3, 6));
+        assert_eq!(polygon.largest_inscribed_rect(), Some([[2, 3], [11, 5]]));
+
+        let mut reversed = polygon.vertices().to_vec();
+        reversed.reverse();
+        let reversed = RectilinearPolygon::new(reversed).unwrap();
+        assert_eq!(reversed.orientation(), Orientation::Clockwise);
+        assert_eq!(reversed.tile_count(), 46);
+    }
+
+    #[test]
+    fn test_polygon_validation() {
+        let err = |v: Vec<[i64; 2]>| RectilinearPolygon::new(v).err().unwrap();
+        assert_eq!(
+            err(vec![[0, 0], [0, 1], [1, 1]]),
+            PolygonError::TooFewVertices(3)
+        );
+        assert_eq!(
+            err(vec![[0, 0], [0, 2], [2, 3], [2, 0]]),
+            PolygonError::NotAxisAligned(1)
+        );
+        assert_eq!(
+            err(vec![[0, 0], [0, 2], [0, 2], [2, 2], [2, 0]]),
+            PolygonError::RepeatedVertex(1)
+        );
+        // A bow tie made of two squares touching at (2, 2).
+        assert_eq!(
+            err(vec![[0, 0], [0, 2], [4, 2], [4, 4], [2, 4], [2, 0]]),
+            PolygonError::SelfIntersection(1, 4)
+        );
+        // Backtracking along the same line.
+        assert_eq!(
+            err(vec![[0, 0], [0, 4], [0, 2], [2, 2], [2, 0]]),
+            PolygonError::SelfIntersection(0, 1)
+        );
+    }
+}
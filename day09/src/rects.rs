@@ -0,0 +1,159 @@
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BinaryHeap},
+};
+
+use crate::polygon::RectilinearPolygon;
+
+/// Rectangle of tiles given by two opposite corners, usually red tiles.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BestRectangle {
+    pub corner_a: [i64; 2],
+    pub corner_b: [i64; 2],
+    /// Number of tiles covered, corners included.
+    pub area: u64,
+}
+
+impl BestRectangle {
+    pub fn new(corner_a: [i64; 2], corner_b: [i64; 2]) -> Self {
+        let area = ((corner_b[0] - corner_a[0]).unsigned_abs() + 1)
+            * ((corner_b[1] - corner_a[1]).unsigned_abs() + 1);
+        Self {
+            corner_a,
+            corner_b,
+            area,
+        }
+    }
+}
+
+/// The rectangles spanned by pairs of red tiles, optionally restricted to
+/// those lying inside the polygon. Pairs are visited in input order, which is
+/// also how ties in area are broken.
+pub struct RectQuery<'a> {
+    pos: &'a [[i64; 2]],
+    polygon: Option<&'a RectilinearPolygon>,
+}
+
+impl<'a> RectQuery<'a> {
+    pub fn unconstrained(pos: &'a [[i64; 2]]) -> Self {
+        Self { pos, polygon: None }
+    }
+
+    pub fn in_polygon(polygon: &'a RectilinearPolygon) -> Self {
+        Self {
+            pos: polygon.vertices(),
+            polygon: Some(polygon),
+        }
+    }
+
+    fn rects(&self) -> impl Iterator<Item = BestRectangle> + '_ {
+        let pos = self.pos;
+        (0..pos.len())
+            .flat_map(move |k1| (k1 + 1..pos.len()).map(move |k2| (k1, k2)))
+            .filter(|&(k1, k2)| {
+                self.polygon
+                    .is_none_or(|poly| poly.contains_vertex_rect(k1, k2))
+            })
+            .map(|(k1, k2)| BestRectangle::new(pos[k1], pos[k2]))
+    }
+
+    pub fn largest(&self) -> Option<BestRectangle> {
+        self.rects()
+            .reduce(|best, r| if r.area > best.area { r } else { best })
+    }
+
+    /// The `n` largest rectangles, largest first, keeping at most `n` of
+    /// them in memory at once.
+    pub fn top(&self, n: usize) -> Vec<BestRectangle> {
+        let mut heap = BinaryHeap::with_capacity(n + 1);
+        for (k, r) in self.rects().enumerate() {
+            heap.push(Reverse((r.area, Reverse(k), [r.corner_a, r.corner_b])));
+            if heap.len() > n {
+                heap.pop();
+            }
+        }
+        heap.into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((area, _, [corner_a, corner_b]))| BestRectangle {
+                corner_a,
+                corner_b,
+                area,
+            })
+            .collect()
+    }
+
+    /// Number of rectangles with an area strictly above `min_area`.
+    pub fn count_above(&self, min_area: u64) -> usize {
+        self.rects().filter(|r| r.area > min_area).count()
+    }
+
+    /// Number of rectangles per area bucket, keyed by the first area of each
+    /// bucket of `width` consecutive areas. Panics if `width` is 0.
+    pub fn area_histogram(&self, width: u64) -> BTreeMap<u64, usize> {
+        assert!(width > 0, "histogram buckets must be at least 1 wide");
+        let mut histogram = BTreeMap::new();
+        for r in self.rects() {
+            *histogram.entry(r.area / width * width).or_insert(0) += 1;
+        }
+        histogram
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{get_red_tile_pos, tests::EXAMPLE};
+
+    #[test]
+    fn test_queries_agree_with_full_sort() {
+        let pos = get_red_tile_pos(EXAMPLE).collect::<Vec<_>>();
+        let polygon = RectilinearPolygon::new(pos.clone()).unwrap();
+        for query in [
+            RectQuery::unconstrained(&pos),
+            RectQuery::in_polygon(&polygon),
+        ] {
+            let mut all = query.rects().collect::<Vec<_>>();
+            all.sort_by_key(|r| Reverse(r.area));
+            for n in 0..=all.len() + 1 {
+                assert_eq!(query.top(n), all[..n.min(all.len())]);
+            }
+            assert_eq!(query.largest(), all.first().copied());
+            for min_area in [0, 10, 24, 50] {
+                let expected = all.iter().filter(|r| r.area > min_area).count();
+                assert_eq!(query.count_above(min_area), expected);
+            }
+            let histogram = query.area_histogram(10);
+            assert_eq!(histogram.values().sum::<usize>(), all.len());
+        }
+    }
+
+    #[test]
+    fn test_example_counts() {
+        let pos = get_red_tile_pos(EXAMPLE).collect::<Vec<_>>();
+        let polygon = RectilinearPolygon::new(pos.clone()).unwrap();
+        let (any, inside) = (
+            RectQuery::unconstrained(&pos),
+            RectQuery::in_polygon(&polygon),
+        );
+        assert_eq!(any.rects().count(), 28);
+        assert_eq!(any.count_above(30), 4);
+        let ties = any.top(2);
+        assert_eq!((ties[0].corner_a, ties[1].corner_a), ([11, 1], [11, 7]));
+        assert_eq!(
+            inside.top(3).iter().map(|r| r.area).collect::<Vec<_>>(),
+            [24, 21, 18]
+        );
+        assert_eq!(inside.count_above(23), 1);
+        assert_eq!(
+            inside.area_histogram(10),
+            BTreeMap::from([(0, 10), (10, 4), (20, 2)])
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "at least 1 wide")]
+    fn test_empty_histogram_buckets() {
+        let pos = get_red_tile_pos(EXAMPLE).collect::<Vec<_>>();
+        RectQuery::unconstrained(&pos).area_histogram(0);
+    }
+}
@@ -0,0 +1,112 @@
+use std::fmt::Write;
+
+use crate::polygon::RectilinearPolygon;
+
+/// Rectangle to highlight on top of the polygon, with its two red corners.
+pub struct Highlight<'a> {
+    pub label: &'a str,
+    pub color: &'a str,
+    pub corners: [[i64; 2]; 2],
+}
+
+/// Draws the loop of red tiles as an SVG with `j` to the right and `i`
+/// downwards, like the puzzle grid. Each tile is a unit square centred on its
+/// coordinates and the view box is fitted to the polygon, so the output size
+/// does not depend on the coordinate range.
+pub fn render(polygon: &RectilinearPolygon, highlights: &[Highlight]) -> String {
+    let vertices = polygon.vertices();
+    let (i_min, i_max) = min_max(vertices.iter().map(|p| p[0]));
+    let (j_min, j_max) = min_max(vertices.iter().map(|p| p[1]));
+    let (height, width) = (i_max - i_min + 1, j_max - j_min + 1);
+    let margin = (height.max(width) as f64 * 0.02).max(1.0);
+    let marker = (height.max(width) as f64 / 400.0).max(0.3);
+    let px_width = 800.0;
+    let px_height = (px_width * height as f64 / width as f64).clamp(100.0, 2400.0);
+
+    let mut out = String::new();
+    writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{px_width}" height="{px_height:.0}" viewBox="{} {} {} {}">"#,
+        j_min as f64 - 0.5 - margin,
+        i_min as f64 - 0.5 - margin,
+        width as f64 + 2.0 * margin,
+        height as f64 + 2.0 * margin,
+    )
+    .unwrap();
+    writeln!(
+        out,
+        r##"<rect x="-100%" y="-100%" width="300%" height="300%" fill="#fff"/>"##
+    )
+    .unwrap();
+    let points = vertices
+        .iter()
+        .map(|p| format!("{},{}", p[1], p[0]))
+        .collect::<Vec<_>>()
+        .join(" ");
+    writeln!(
+        out,
+        r##"<polygon points="{points}" fill="#c8e6c9" stroke="#2e7d32" stroke-width="2" vector-effect="non-scaling-stroke"/>"##
+    )
+    .unwrap();
+    for h in highlights {
+        let [p1, p2] = h.corners;
+        writeln!(
+            out,
+            r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}" fill-opacity="0.35" stroke="{}" stroke-width="2" vector-effect="non-scaling-stroke"><title>{}: {:?} to {:?}</title></rect>"#,
+            p1[1].min(p2[1]) as f64 - 0.5,
+            p1[0].min(p2[0]) as f64 - 0.5,
+            (p1[1] - p2[1]).abs() + 1,
+            (p1[0] - p2[0]).abs() + 1,
+            h.color,
+            h.color,
+            h.label,
+            p1,
+            p2,
+        )
+        .unwrap();
+    }
+    for p in vertices {
+        writeln!(
+            out,
+            r##"<circle cx="{}" cy="{}" r="{marker}" fill="#d32f2f"/>"##,
+            p[1], p[0]
+        )
+        .unwrap();
+    }
+    writeln!(out, "</svg>").unwrap();
+    out
+}
+
+fn min_max(values: impl Iterator<Item = i64>) -> (i64, i64) {
+    values.fold((i64::MAX, i64::MIN), |(lo, hi), v| (lo.min(v), hi.max(v)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{get_red_tile_pos, tests::EXAMPLE};
+
+    #[test]
+    fn test_render() {
+        let polygon = RectilinearPolygon::new(get_red_tile_pos(EXAMPLE).collect()).unwrap();
+        let svg = render(
+            &polygon,
+            &[Highlight {
+                label: "part 2",
+                color: "blue",
+                corners: [[9, 5], [2, 3]],
+            }],
+        );
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.ends_with("</svg>\n"));
+        assert!(svg.contains(r#"viewBox="-0.5 0.5 9 12""#));
+        assert!(svg.contains(r#"points="1,7 1,11 7,11 7,9 5,9 5,2 3,2 3,7""#));
+        assert!(svg.contains(r#"<rect x="2.5" y="1.5" width="3" height="8" fill="blue""#));
+        assert_eq!(svg.matches("<circle ").count(), 8);
+
+        // Huge coordinates only change the view box, not the amount of output.
+        let scaled = get_red_tile_pos(EXAMPLE).map(|p| p.map(|c| c * 10_000));
+        let polygon = RectilinearPolygon::new(scaled.collect()).unwrap();
+        assert!(render(&polygon, &[]).len() < 2 * svg.len());
+    }
+}
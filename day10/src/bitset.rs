@@ -0,0 +1,126 @@
+use std::ops::BitXorAssign;
+
+/// Growable set of small integers, packed in 64-bit words.
+#[derive(Clone, Debug)]
+pub enum BitSet {
+    /// Fast path for sets whose members all fit in a single word.
+    Word(u64),
+    Words(Vec<u64>),
+}
+
+impl BitSet {
+    pub fn with_width(width: usize) -> Self {
+        if width <= 64 {
+            BitSet::Word(0)
+        } else {
+            BitSet::Words(vec![0; width.div_ceil(64)])
+        }
+    }
+
+    pub fn from_positions(width: usize, positions: impl IntoIterator<Item = usize>) -> Self {
+        let mut set = Self::with_width(width);
+        for bit in positions {
+            set.insert(bit);
+        }
+        set
+    }
+
+    fn words(&self) -> &[u64] {
+        match self {
+            BitSet::Word(w) => std::slice::from_ref(w),
+            BitSet::Words(ws) => ws,
+        }
+    }
+
+    fn grow_to(&mut self, n_words: usize) -> &mut [u64] {
+        if let BitSet::Word(w) = *self
+            && n_words > 1
+        {
+            *self = BitSet::Words(vec![w]);
+        }
+        match self {
+            BitSet::Word(w) => std::slice::from_mut(w),
+            BitSet::Words(ws) => {
+                if ws.len() < n_words {
+                    ws.resize(n_words, 0);
+                }
+                ws
+            }
+        }
+    }
+
+    pub fn insert(&mut self, bit: usize) {
+        self.grow_to(bit / 64 + 1)[bit / 64] |= 1 << (bit % 64);
+    }
+
+    pub fn toggle(&mut self, bit: usize) {
+        self.grow_to(bit / 64 + 1)[bit / 64] ^= 1 << (bit % 64);
+    }
+
+    pub fn contains(&self, bit: usize) -> bool {
+        self.words()
+            .get(bit / 64)
+            .is_some_and(|w| w >> (bit % 64) & 1 == 1)
+    }
+
+    pub fn count_ones(&self) -> u32 {
+        self.words().iter().map(|w| w.count_ones()).sum()
+    }
+
+    pub fn iter_ones(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words().iter().enumerate().flat_map(|(i, &w)| {
+            (0..64)
+                .filter(move |b| w >> b & 1 == 1)
+                .map(move |b| i * 64 + b)
+        })
+    }
+}
+
+impl BitXorAssign<&BitSet> for BitSet {
+    fn bitxor_assign(&mut self, rhs: &BitSet) {
+        if let (BitSet::Word(a), BitSet::Word(b)) = (&mut *self, rhs) {
+            *a ^= b;
+            return;
+        }
+        let words = self.grow_to(rhs.words().len());
+        words.iter_mut().zip(rhs.words()).for_each(|(a, b)| *a ^= b);
+    }
+}
+
+impl PartialEq for BitSet {
+    fn eq(&self, other: &Self) -> bool {
+        let (a, b) = (self.words(), other.words());
+        let (short, long) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+        long[..short.len()] == *short && long[short.len()..].iter().all(|&w| w == 0)
+    }
+}
+
+impl Eq for BitSet {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bitset_grows_past_one_word() {
+        let mut set = BitSet::with_width(10);
+        set.insert(3);
+        assert_eq!(set, BitSet::Word(0b1000));
+        set.insert(130);
+        assert!(matches!(set, BitSet::Words(ref ws) if ws.len() == 3));
+        assert!(set.contains(3) && set.contains(130) && !set.contains(200));
+        assert_eq!(set.iter_ones().collect::<Vec<_>>(), vec![3, 130]);
+        set.toggle(130);
+        assert_eq!(set, BitSet::Word(0b1000));
+    }
+
+    #[test]
+    fn test_bitset_xor() {
+        let mut a = BitSet::from_positions(8, [0, 2]);
+        a ^= &BitSet::from_positions(8, [2, 5]);
+        assert_eq!(a, BitSet::Word(0b100001));
+        a ^= &BitSet::from_positions(100, [5, 99]);
+        assert_eq!(a, BitSet::from_positions(100, [0, 99]));
+        assert_eq!(a.count_ones(), 2);
+    }
+}
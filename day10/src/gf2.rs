@@ -0,0 +1,197 @@
+use std::fmt;
+
+use crate::bitset::BitSet;
+
+/// Most free buttons whose combinations `min_presses` will try, which is
+/// 2^`MAX_FREE` candidate solutions.
+pub const MAX_FREE: usize = 24;
+
+#[derive(Debug, PartialEq)]
+pub enum Unsolved {
+    /// No combination of buttons reaches the target.
+    Unreachable,
+    /// The target is reachable, but through too many combinations of free
+    /// buttons to search for the fewest presses.
+    TooManyFree(usize),
+}
+
+impl fmt::Display for Unsolved {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Unsolved::Unreachable => write!(f, "no combination of buttons reaches the target"),
+            Unsolved::TooManyFree(free) => write!(
+                f,
+                "{free} free buttons, more than the {MAX_FREE} that can be searched"
+            ),
+        }
+    }
+}
+
+/// Finds the smallest set of buttons whose XOR toggles exactly the `target`
+/// lights, by Gaussian elimination over GF(2).
+///
+/// Each button is given as the positions of the lights it toggles on a panel of
+/// `width` lights. Returns the indices of the buttons to press, or why they
+/// couldn't be found.
+pub fn min_presses(
+    width: usize,
+    buttons: &[BitSet],
+    target: &BitSet,
+) -> Result<Vec<usize>, Unsolved> {
+    let n = buttons.len();
+
+    // One equation per light over the button variables, with the target state
+    // of the light stored in bit `n`.
+    let mut rows = vec![BitSet::with_width(n + 1); width];
+    for (b, button) in buttons.iter().enumerate() {
+        for l in button.iter_ones() {
+            rows[l].toggle(b);
+        }
+    }
+    for l in target.iter_ones() {
+        rows[l].toggle(n);
+    }
+
+    let mut pivots = vec![];
+    for b in 0..n {
+        let Some(r) = (pivots.len()..width).find(|&r| rows[r].contains(b)) else {
+            continue;
+        };
+        rows.swap(pivots.len(), r);
+        let pivot = rows[pivots.len()].clone();
+        for (r, row) in rows.iter_mut().enumerate() {
+            if r != pivots.len() && row.contains(b) {
+                *row ^= &pivot;
+            }
+        }
+        pivots.push(b);
+    }
+    if rows[pivots.len()..].iter().any(|row| row.contains(n)) {
+        return Err(Unsolved::Unreachable);
+    }
+    if n - pivots.len() > MAX_FREE {
+        return Err(Unsolved::TooManyFree(n - pivots.len()));
+    }
+
+    // Every solution is the particular one (free buttons unpressed) plus a
+    // combination of the null space basis; walk them in Gray-code order.
+    let particular = BitSet::from_positions(
+        n,
+        pivots
+            .iter()
+            .enumerate()
+            .filter(|&(r, _)| rows[r].contains(n))
+            .map(|(_, &b)| b),
+    );
+    let basis = (0..n)
+        .filter(|b| !pivots.contains(b))
+        .map(|free| {
+            let mut v = BitSet::from_positions(n, [free]);
+            for (r, &b) in pivots.iter().enumerate() {
+                if rows[r].contains(free) {
+                    v.toggle(b);
+                }
+            }
+            v
+        })
+        .collect::<Vec<_>>();
+    let mut current = particular;
+    let mut best = current.clone();
+    for step in 1..1u64 << basis.len() {
+        current ^= &basis[step.trailing_zeros() as usize];
+        if current.count_ones() < best.count_ones() {
+            best = current.clone();
+        }
+    }
+    Ok(best.iter_ones().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn brute_force(width: usize, buttons: &[Vec<usize>], target: &[usize]) -> Option<usize> {
+        (0..1u32 << buttons.len())
+            .filter(|subset| {
+                let mut lights = vec![false; width];
+                for (b, button) in buttons.iter().enumerate() {
+                    if subset & (1 << b) != 0 {
+                        for &l in button {
+                            lights[l] ^= true;
+                        }
+                    }
+                }
+                (0..width).all(|l| lights[l] == target.contains(&l))
+            })
+            .map(|subset| subset.count_ones() as usize)
+            .min()
+    }
+
+    fn bitsets(buttons: &[Vec<usize>]) -> Vec<BitSet> {
+        buttons
+            .iter()
+            .map(|b| BitSet::from_positions(64, b.iter().copied()))
+            .collect()
+    }
+
+    #[test]
+    fn test_min_presses_matches_brute_force() {
+        let buttons = vec![
+            vec![0, 2, 3, 4],
+            vec![2, 3],
+            vec![0, 4],
+            vec![0, 1, 2],
+            vec![1, 2, 3, 4],
+            vec![3, 4],
+            vec![3],
+            vec![2, 5],
+        ];
+        for mask in 0..1u32 << 7 {
+            let target = (0..7).filter(|&l| mask & (1 << l) != 0).collect::<Vec<_>>();
+            let presses = min_presses(
+                7,
+                &bitsets(&buttons),
+                &BitSet::from_positions(7, target.clone()),
+            );
+            assert_eq!(
+                presses.as_ref().ok().map(Vec::len),
+                brute_force(7, &buttons, &target)
+            );
+        }
+    }
+
+    #[test]
+    fn test_min_presses_unsolvable() {
+        let buttons = bitsets(&[vec![0, 1], vec![1, 2]]);
+        let target = |t: &[usize]| BitSet::from_positions(3, t.iter().copied());
+        let unreachable = Err(Unsolved::Unreachable);
+        assert_eq!(min_presses(3, &buttons, &target(&[0])), unreachable);
+        assert_eq!(min_presses(3, &buttons, &target(&[0, 2])), Ok(vec![0, 1]));
+        assert_eq!(min_presses(3, &[], &target(&[1])), unreachable);
+        assert_eq!(min_presses(3, &[], &target(&[])), Ok(vec![]));
+
+        // Buttons that toggle nothing are all free.
+        let idle = vec![BitSet::with_width(3); 64];
+        assert_eq!(
+            min_presses(3, &idle, &target(&[])),
+            Err(Unsolved::TooManyFree(64))
+        );
+        assert_eq!(min_presses(3, &idle[..MAX_FREE], &target(&[])), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_min_presses_wide_panel() {
+        let buttons = bitsets(&[vec![0, 70], vec![70, 140], vec![140]]);
+        let buttons = buttons
+            .into_iter()
+            .chain([BitSet::from_positions(150, [3, 149])])
+            .collect::<Vec<_>>();
+        let target = BitSet::from_positions(150, [0, 3]);
+        assert_eq!(
+            min_presses(150, &buttons, &target),
+            Err(Unsolved::Unreachable)
+        );
+        let target = BitSet::from_positions(150, [0, 140, 3, 149]);
+        assert_eq!(min_presses(150, &buttons, &target), Ok(vec![0, 1, 3]));
+    }
+}
@@ -0,0 +1,244 @@
+/// Minimises `x.iter().sum()` subject to `a · x = b` with `x` a vector of
+/// non-negative integers.
+///
+/// `a` holds one row per equation and must only contain non-negative
+/// coefficients, which is what bounds every variable by the right-hand side.
+/// The system is reduced over the rationals, then the free variables are
+/// searched by branch-and-bound. Returns `None` when no solution exists.
+pub fn min_sum_solution(a: &[Vec<i64>], b: &[i64]) -> Option<Vec<i64>> {
+    let n = a.first().map_or(0, |row| row.len());
+    if b.iter().any(|&bi| bi < 0) {
+        return None;
+    }
+
+    // Fraction-free reduced row echelon form of [a | b]: every pivot row ends
+    // up as `d·x_p + Σ a_pf·x_f = c` over the free variables `f`.
+    let mut rows = a
+        .iter()
+        .zip(b)
+        .map(|(row, &bi)| row.iter().copied().chain([bi]).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let mut pivots = vec![];
+    for col in 0..n {
+        let rank = pivots.len();
+        let Some(r) = (rank..rows.len()).find(|&r| rows[r][col] != 0) else {
+            continue;
+        };
+        rows.swap(rank, r);
+        if rows[rank][col] < 0 {
+            rows[rank].iter_mut().for_each(|v| *v = -*v);
+        }
+        let pivot = rows[rank].clone();
+        for (r, row) in rows.iter_mut().enumerate() {
+            if r != rank && row[col] != 0 {
+                let factor = row[col];
+                row.iter_mut()
+                    .zip(&pivot)
+                    .for_each(|(v, &p)| *v = *v * pivot[col] - p * factor);
+                normalize(row);
+            }
+        }
+        pivots.push(col);
+    }
+    if rows[pivots.len()..].iter().any(|row| row[n] != 0) {
+        return None;
+    }
+    rows.truncate(pivots.len());
+
+    // Pressing a button can't exceed any counter it feeds.
+    let free = (0..n).filter(|c| !pivots.contains(c)).collect::<Vec<_>>();
+    let upper = free
+        .iter()
+        .map(|&f| {
+            a.iter()
+                .zip(b)
+                .filter(|(row, _)| row[f] > 0)
+                .map(|(row, bi)| bi / row[f])
+                .min()
+                .unwrap_or(0)
+        })
+        .collect::<Vec<_>>();
+
+    // The objective is linear in the free variables once the pivots are
+    // substituted; scale it by the lcm of the pivot coefficients.
+    let scale = rows
+        .iter()
+        .zip(&pivots)
+        .fold(1, |l, (row, &p)| lcm(l, row[p]));
+    let base = rows
+        .iter()
+        .zip(&pivots)
+        .map(|(row, &p)| scale / row[p] * row[n])
+        .sum::<i64>();
+    let weights = free
+        .iter()
+        .map(|&f| {
+            scale
+                - rows
+                    .iter()
+                    .zip(&pivots)
+                    .map(|(row, &p)| scale / row[p] * row[f])
+                    .sum::<i64>()
+        })
+        .collect::<Vec<_>>();
+
+    let mut search = Search {
+        rows: &rows,
+        pivots: &pivots,
+        free: &free,
+        upper: &upper,
+        weights: &weights,
+        assigned: vec![0; free.len()],
+        best: None,
+    };
+    search.branch(0, base);
+    let (_, free_values) = search.best?;
+
+    let mut x = vec![0; n];
+    for (&f, &v) in free.iter().zip(&free_values) {
+        x[f] = v;
+    }
+    for (row, &p) in rows.iter().zip(&pivots) {
+        let rest = free.iter().map(|&f| row[f] * x[f]).sum::<i64>();
+        x[p] = (row[n] - rest) / row[p];
+    }
+    Some(x)
+}
+
+struct Search<'a> {
+    rows: &'a [Vec<i64>],
+    pivots: &'a [usize],
+    free: &'a [usize],
+    upper: &'a [i64],
+    weights: &'a [i64],
+    assigned: Vec<i64>,
+    best: Option<(i64, Vec<i64>)>,
+}
+
+impl Search<'_> {
+    /// Assigns the free variables from `depth` on; `cost` is the scaled
+    /// objective with the remaining free variables at zero.
+    fn branch(&mut self, depth: usize, cost: i64) {
+        let optimistic = cost
+            + (depth..self.free.len())
+                .map(|k| (self.weights[k] * self.upper[k]).min(0))
+                .sum::<i64>();
+        if self.best.as_ref().is_some_and(|(b, _)| optimistic >= *b) || !self.pivots_feasible(depth)
+        {
+            return;
+        }
+        if depth == self.free.len() {
+            self.best = Some((cost, self.assigned.clone()));
+            return;
+        }
+        for v in 0..=self.upper[depth] {
+            self.assigned[depth] = v;
+            self.branch(depth + 1, cost + self.weights[depth] * v);
+        }
+        self.assigned[depth] = 0;
+    }
+
+    /// Whether every pivot variable can still be a non-negative integer given
+    /// the free variables assigned so far.
+    fn pivots_feasible(&self, depth: usize) -> bool {
+        let n = self.pivots.len() + self.free.len();
+        self.rows.iter().zip(self.pivots).all(|(row, &p)| {
+            let fixed = row[n]
+                - (0..depth)
+                    .map(|k| row[self.free[k]] * self.assigned[k])
+                    .sum::<i64>();
+            if depth == self.free.len() {
+                return fixed >= 0 && fixed % row[p] == 0;
+            }
+            // Largest value `d·x_p` can still reach with the others in range.
+            let reach = fixed
+                - (depth..self.free.len())
+                    .map(|k| (row[self.free[k]] * self.upper[k]).min(0))
+                    .sum::<i64>();
+            reach >= 0
+        })
+    }
+}
+
+fn gcd(a: i64, b: i64) -> i64 {
+    if b == 0 { a.abs() } else { gcd(b, a % b) }
+}
+
+fn lcm(a: i64, b: i64) -> i64 {
+    a / gcd(a, b) * b
+}
+
+fn normalize(row: &mut [i64]) {
+    let g = row.iter().fold(0, |g, &v| gcd(g, v));
+    if g > 1 {
+        row.iter_mut().for_each(|v| *v /= g);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn brute_force(a: &[Vec<i64>], b: &[i64], max: i64) -> Option<i64> {
+        let n = a[0].len();
+        let mut x = vec![0; n];
+        let mut best = None;
+        loop {
+            if a.iter()
+                .zip(b)
+                .all(|(row, &bi)| row.iter().zip(&x).map(|(r, v)| r * v).sum::<i64>() == bi)
+            {
+                let sum = x.iter().sum::<i64>();
+                best = Some(best.map_or(sum, |b: i64| b.min(sum)));
+            }
+            let Some(k) = (0..n).find(|&k| x[k] < max) else {
+                return best;
+            };
+            x[k] += 1;
+            x[..k].iter_mut().for_each(|v| *v = 0);
+        }
+    }
+
+    #[test]
+    fn test_min_sum_solution_matches_brute_force() {
+        let a = vec![
+            vec![1, 0, 1, 1, 0],
+            vec![0, 1, 1, 0, 1],
+            vec![1, 1, 0, 1, 1],
+        ];
+        for b0 in 0..5 {
+            for b1 in 0..5 {
+                for b2 in 0..5 {
+                    let b = [b0, b1, b2];
+                    let x = min_sum_solution(&a, &b);
+                    if let Some(x) = &x {
+                        assert!(x.iter().all(|&v| v >= 0));
+                        for (row, &bi) in a.iter().zip(&b) {
+                            assert_eq!(row.iter().zip(x).map(|(r, v)| r * v).sum::<i64>(), bi);
+                        }
+                    }
+                    assert_eq!(x.map(|x| x.iter().sum()), brute_force(&a, &b, 4));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_min_sum_solution_many_buttons() {
+        // 20 buttons on 8 counters, far beyond enumerating button subsets.
+        let a = (0..8)
+            .map(|i| (0..20).map(|j| ((i * 7 + j * 3) % 5 < 2) as i64).collect())
+            .collect::<Vec<Vec<i64>>>();
+        let x0 = (0..20).map(|j| (j * 11 % 7) as i64).collect::<Vec<_>>();
+        let b = a
+            .iter()
+            .map(|row| row.iter().zip(&x0).map(|(r, v)| r * v).sum())
+            .collect::<Vec<i64>>();
+        let x = min_sum_solution(&a, &b).unwrap();
+        assert!(x.iter().sum::<i64>() <= x0.iter().sum::<i64>());
+        for (row, &bi) in a.iter().zip(&b) {
+            assert_eq!(row.iter().zip(&x).map(|(r, v)| r * v).sum::<i64>(), bi);
+        }
+        assert_eq!(min_sum_solution(&[vec![2, 4]], &[3]), None);
+    }
+}
@@ -0,0 +1,313 @@
+use std::fmt;
+
+use crate::{bitset::BitSet, gf2, ilp};
+
+fn light_pattern_to_bitset(s: &str) -> Option<BitSet> {
+    s.chars().all(|c| c == '.' || c == '#').then(|| {
+        BitSet::from_positions(
+            s.len(),
+            s.chars()
+                .enumerate()
+                .filter(|&(_, c)| c == '#')
+                .map(|(i, _)| i),
+        )
+    })
+}
+
+#[test]
+fn test_light_pattern_to_bitset() {
+    assert_eq!(light_pattern_to_bitset(".##."), Some(BitSet::Word(0b110)));
+    assert_eq!(light_pattern_to_bitset("...#."), Some(BitSet::Word(0b1000)));
+    assert_eq!(
+        light_pattern_to_bitset(".###.#"),
+        Some(BitSet::Word(0b101110))
+    );
+    let wide = format!("{}#", ".".repeat(99));
+    assert_eq!(
+        light_pattern_to_bitset(&wide),
+        Some(BitSet::Words(vec![0, 1 << 35]))
+    );
+    assert_eq!(light_pattern_to_bitset(".#x"), None);
+}
+
+/// Light indices toggled by a button. They are checked against the panel
+/// width before any `BitSet` is built, so a huge index can't allocate.
+fn btn_pattern_to_positions(s: &str) -> Option<Vec<usize>> {
+    s.split(',')
+        .map(|num| num.trim().parse::<usize>().ok())
+        .collect()
+}
+
+#[test]
+fn test_btn_pattern_to_positions() {
+    assert_eq!(btn_pattern_to_positions("3"), Some(vec![3]));
+    assert_eq!(btn_pattern_to_positions("1,3"), Some(vec![1, 3]));
+    assert_eq!(btn_pattern_to_positions("0, 2"), Some(vec![0, 2]));
+    assert_eq!(
+        btn_pattern_to_positions("2,100000000000000"),
+        Some(vec![2, 100000000000000])
+    );
+    assert_eq!(btn_pattern_to_positions("1,,2"), None);
+    assert_eq!(
+        BitSet::from_positions(65, btn_pattern_to_positions("2,64").unwrap()),
+        BitSet::Words(vec![0b100, 1])
+    );
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ParseError {
+    MissingLights,
+    InvalidLights(String),
+    InvalidButton(String),
+    ButtonOutOfRange {
+        token: String,
+        index: usize,
+        width: usize,
+    },
+    MissingJoltage,
+    InvalidJoltage(String),
+    JoltageLength {
+        token: String,
+        len: usize,
+        width: usize,
+    },
+    UnexpectedToken(String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::MissingLights => write!(f, "missing light pattern"),
+            ParseError::InvalidLights(token) => write!(f, "invalid light pattern `{token}`"),
+            ParseError::InvalidButton(token) => write!(f, "invalid button `{token}`"),
+            ParseError::ButtonOutOfRange {
+                token,
+                index,
+                width,
+            } => write!(
+                f,
+                "button `{token}` toggles light {index} but the panel has {width} lights"
+            ),
+            ParseError::MissingJoltage => write!(f, "missing joltage requirements"),
+            ParseError::InvalidJoltage(token) => {
+                write!(f, "invalid joltage requirements `{token}`")
+            }
+            ParseError::JoltageLength { token, len, width } => write!(
+                f,
+                "joltage requirements `{token}` have {len} counters but the panel has {width} lights"
+            ),
+            ParseError::UnexpectedToken(token) => write!(f, "unexpected token `{token}`"),
+        }
+    }
+}
+
+pub struct Machine {
+    /// Number of lights on the panel.
+    pub width: usize,
+    pub lights: BitSet,
+    pub buttons: Vec<BitSet>,
+    pub joltage: Vec<i64>,
+}
+
+/// How many times each button of a machine is pressed.
+#[derive(Debug, PartialEq)]
+pub struct MachineSolution {
+    pub presses: Vec<u64>,
+}
+
+impl MachineSolution {
+    pub fn total(&self) -> u64 {
+        self.presses.iter().sum()
+    }
+}
+
+impl fmt::Display for MachineSolution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let presses = self
+            .presses
+            .iter()
+            .enumerate()
+            .filter(|&(_, &n)| n > 0)
+            .map(|(b, n)| format!("#{b}x{n}"))
+            .collect::<Vec<_>>();
+        write!(f, "{}", presses.join(" "))
+    }
+}
+
+impl Machine {
+    pub fn parse(line: &str) -> Result<Self, ParseError> {
+        let mut iter = line.split_whitespace().peekable();
+        let token = iter.next().ok_or(ParseError::MissingLights)?;
+        let pattern = token
+            .strip_prefix('[')
+            .and_then(|t| t.strip_suffix(']'))
+            .ok_or_else(|| ParseError::InvalidLights(token.to_string()))?;
+        let width = pattern.len();
+        let lights = light_pattern_to_bitset(pattern)
+            .ok_or_else(|| ParseError::InvalidLights(token.to_string()))?;
+
+        let mut buttons = vec![];
+        while let Some(token) = iter.next_if(|t| t.starts_with('(')) {
+            let positions = token
+                .strip_suffix(')')
+                .and_then(|t| btn_pattern_to_positions(&t[1..]))
+                .ok_or_else(|| ParseError::InvalidButton(token.to_string()))?;
+            if let Some(&index) = positions.iter().find(|&&i| i >= width) {
+                return Err(ParseError::ButtonOutOfRange {
+                    token: token.to_string(),
+                    index,
+                    width,
+                });
+            }
+            buttons.push(BitSet::from_positions(width, positions));
+        }
+
+        let token = iter.next().ok_or(ParseError::MissingJoltage)?;
+        let joltage = token
+            .strip_prefix('{')
+            .and_then(|t| t.strip_suffix('}'))
+            .and_then(|t| {
+                t.split(',')
+                    .map(|num| num.trim().parse::<u32>().ok().map(i64::from))
+                    .collect::<Option<Vec<_>>>()
+            })
+            .ok_or_else(|| ParseError::InvalidJoltage(token.to_string()))?;
+        if joltage.len() != width {
+            return Err(ParseError::JoltageLength {
+                token: token.to_string(),
+                len: joltage.len(),
+                width,
+            });
+        }
+        if let Some(token) = iter.next() {
+            return Err(ParseError::UnexpectedToken(token.to_string()));
+        }
+
+        Ok(Self {
+            width,
+            lights,
+            buttons,
+            joltage,
+        })
+    }
+
+    /// Fewest presses that turn on exactly the target lights.
+    pub fn solve_lights(&self) -> Result<MachineSolution, gf2::Unsolved> {
+        let pressed = gf2::min_presses(self.width, &self.buttons, &self.lights)?;
+        let mut presses = vec![0; self.buttons.len()];
+        for b in pressed {
+            presses[b] = 1;
+        }
+        Ok(MachineSolution { presses })
+    }
+
+    /// Fewest presses that bring every counter to its joltage.
+    pub fn solve_joltage(&self) -> Option<MachineSolution> {
+        let a = (0..self.joltage.len())
+            .map(|i| self.buttons.iter().map(|b| b.contains(i) as i64).collect())
+            .collect::<Vec<_>>();
+        let x = ilp::min_sum_solution(&a, &self.joltage)?;
+        Some(MachineSolution {
+            presses: x.into_iter().map(|v| v as u64).collect(),
+        })
+    }
+
+    pub fn lights_after(&self, presses: &[u64]) -> BitSet {
+        let mut lights = BitSet::with_width(self.width);
+        for (button, &n) in self.buttons.iter().zip(presses) {
+            if n % 2 == 1 {
+                lights ^= button;
+            }
+        }
+        lights
+    }
+
+    pub fn joltage_after(&self, presses: &[u64]) -> Vec<i64> {
+        let mut joltage = vec![0; self.joltage.len()];
+        for (button, &n) in self.buttons.iter().zip(presses) {
+            for i in button.iter_ones() {
+                joltage[i] += n as i64;
+            }
+        }
+        joltage
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_machine_witness() {
+        let machine = Machine::parse("[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}").unwrap();
+        assert_eq!(machine.width, 4);
+        assert_eq!(machine.buttons.len(), 6);
+        assert_eq!(machine.joltage, vec![3, 5, 4, 7]);
+
+        let lights = machine.solve_lights().unwrap();
+        assert_eq!(lights.total(), 2);
+        assert_eq!(machine.lights_after(&lights.presses), machine.lights);
+
+        let joltage = machine.solve_joltage().unwrap();
+        assert_eq!(joltage.total(), 10);
+        assert_eq!(machine.joltage_after(&joltage.presses), machine.joltage);
+        assert_eq!(
+            MachineSolution {
+                presses: vec![1, 0, 2]
+            }
+            .to_string(),
+            "#0x1 #2x2"
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = |line: &str| Machine::parse(line).err().unwrap();
+        assert_eq!(err(""), ParseError::MissingLights);
+        assert_eq!(
+            err("[.#x] (0) {1,1,1}"),
+            ParseError::InvalidLights("[.#x]".into())
+        );
+        assert_eq!(
+            err("[.#] (0,a) {1,1}"),
+            ParseError::InvalidButton("(0,a)".into())
+        );
+        assert_eq!(
+            err("[.#] (0) (1,2) {1,1}"),
+            ParseError::ButtonOutOfRange {
+                token: "(1,2)".into(),
+                index: 2,
+                width: 2
+            }
+        );
+        assert_eq!(err("[.#] (0)"), ParseError::MissingJoltage);
+        assert_eq!(
+            err("[.#] (0) {1,-1}"),
+            ParseError::InvalidJoltage("{1,-1}".into())
+        );
+        assert_eq!(
+            err("[.#] (0) {1,1,1}"),
+            ParseError::JoltageLength {
+                token: "{1,1,1}".into(),
+                len: 3,
+                width: 2
+            }
+        );
+        assert_eq!(
+            err("[.#] (0) {1,1} (1)"),
+            ParseError::UnexpectedToken("(1)".into())
+        );
+        assert_eq!(
+            err("[.#] (0) (100000000000000) {1,1}"),
+            ParseError::ButtonOutOfRange {
+                token: "(100000000000000)".into(),
+                index: 100000000000000,
+                width: 2
+            }
+        );
+        assert_eq!(
+            err("[.#] (0) (5) {1,1}").to_string(),
+            "button `(5)` toggles light 5 but the panel has 2 lights"
+        );
+    }
+}
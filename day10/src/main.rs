@@ -1,141 +1,88 @@
-use std::{collections::HashSet, path::PathBuf, time::Instant};
+use std::{path::PathBuf, time::Instant};
 
-fn light_pattern_to_u64(s: &str) -> u64 {
-    s.chars()
-        .enumerate()
-        .fold(0, |acc, (i, c)| if c == '#' { acc | (1 << i) } else { acc })
-}
-
-#[test]
-fn test_light_pattern_to_u64() {
-    assert_eq!(light_pattern_to_u64(".##."), 0b110);
-    assert_eq!(light_pattern_to_u64("...#."), 0b1000);
-    assert_eq!(light_pattern_to_u64(".###.#"), 0b101110);
-}
+mod bitset;
+mod gf2;
+mod ilp;
+mod machine;
 
-pub fn btn_pattern_to_u64(s: &str) -> u64 {
-    s.split(',')
-        .map(|num| num.trim().parse::<usize>().unwrap())
-        .fold(0u64, |acc, idx| acc | (1 << idx))
-}
+use machine::{Machine, MachineSolution};
 
-#[test]
-fn test_btn_pattern_to_u64() {
-    assert_eq!(btn_pattern_to_u64("3"), 0b1000);
-    assert_eq!(btn_pattern_to_u64("1,3"), 0b1010);
-    assert_eq!(btn_pattern_to_u64("0,2"), 0b101);
-    assert_eq!(btn_pattern_to_u64("4,5"), 0b110000);
-}
-
-fn part1(input: &str) -> u64 {
+fn part1(input: &str) -> Result<u64, String> {
     input
         .trim()
         .lines()
-        .map(|line| {
-            let mut iter = line.split_whitespace().peekable();
-            let target = light_pattern_to_u64(
-                iter.next()
-                    .unwrap()
-                    .trim_start_matches('[')
-                    .trim_end_matches(']'),
-            );
-            let btns = {
-                let mut b = vec![];
-                while iter.peek().is_some() && iter.peek().unwrap().starts_with("(") {
-                    b.push(btn_pattern_to_u64(
-                        iter.next()
-                            .unwrap()
-                            .trim_start_matches('(')
-                            .trim_end_matches(')'),
-                    ));
-                }
-                b
-            };
-            let mut current_sets = HashSet::new();
-            current_sets.insert(0u64);
-            for i in 1..btns.len() {
-                current_sets = current_sets
-                    .iter()
-                    .flat_map(|&lights| btns.iter().map(move |&btn| lights ^ btn))
-                    .collect();
-                if current_sets.contains(&target) {
-                    return i as u64;
-                }
-            }
-            panic!("No solution found for line: {}", line);
+        .enumerate()
+        .map(|(line_no, line)| {
+            let machine =
+                Machine::parse(line).map_err(|err| format!("line {}: {}", line_no + 1, err))?;
+            machine
+                .solve_lights()
+                .map(|solution| solution.total())
+                .map_err(|err| {
+                    format!(
+                        "No solution for machine on line {} ({}): {}",
+                        line_no + 1,
+                        err,
+                        line
+                    )
+                })
         })
         .sum()
 }
 
-fn part2(input: &str) -> u64 {
+fn part2(input: &str) -> Result<u64, String> {
     input
         .trim()
         .lines()
-        .map(|line| {
-            let mut iter = line.split_whitespace().skip(1).peekable();
-            let mut btns: Vec<Vec<usize>> = vec![];
-            while let Some(next_str) = iter.peek()
-                && next_str.starts_with("(")
-            {
-                btns.push(
-                    iter.next()
-                        .unwrap()
-                        .trim_start_matches('(')
-                        .trim_end_matches(')')
-                        .split(',')
-                        .map(|num| num.trim().parse::<usize>().unwrap())
-                        .collect(),
-                );
-            }
-            let target: Vec<i64> = iter
-                .next()
-                .unwrap()
-                .trim_start_matches('{')
-                .trim_end_matches('}')
-                .split(',')
-                .map(|num| num.trim().parse::<i64>().unwrap())
-                .collect();
-            let m = target.len();
-            let combos: Vec<(Vec<i64>, i64)> = (0..(1 << btns.len()))
-                .map(|n| {
-                    let mut counter = vec![0i64; m];
-                    let mut nb_pressed = 0i64;
-                    for (j, btn) in btns.iter().enumerate() {
-                        if (n & (1 << j)) != 0 {
-                            nb_pressed += 1;
-                            for &idx in btn {
-                                counter[idx] += 1;
-                            }
-                        }
-                    }
-                    (counter, nb_pressed)
-                })
-                .collect();
-            fn solve(counter: &[i64], combos: &[(Vec<i64>, i64)]) -> Option<i64> {
-                if counter.iter().all(|&x| x == 0) {
-                    return Some(0);
-                }
-                combos
-                    .iter()
-                    .filter(|(comb, _)| {
-                        comb.iter().zip(counter).all(|(a, b)| a <= b)
-                            && comb.iter().zip(counter).all(|(a, b)| a % 2 == b % 2)
-                    })
-                    .filter_map(|(comb, nb_pressed)| {
-                        let next: Vec<i64> = counter
-                            .iter()
-                            .zip(comb)
-                            .map(|(c, cc)| (c - cc) / 2)
-                            .collect();
-                        solve(&next, combos).map(|rec| 2 * rec + nb_pressed)
-                    })
-                    .min()
-            }
-            solve(&target, &combos).unwrap() as u64
+        .enumerate()
+        .map(|(line_no, line)| {
+            let machine =
+                Machine::parse(line).map_err(|err| format!("line {}: {}", line_no + 1, err))?;
+            machine
+                .solve_joltage()
+                .map(|solution| solution.total())
+                .ok_or_else(|| format!("No solution for machine on line {}: {}", line_no + 1, line))
         })
         .sum()
 }
 
+fn print_table(input: &str) {
+    println!(
+        "{:>4}  {:>6}  {:>6}  {:<5}  presses (lights | joltage)",
+        "line", "part1", "part2", "check"
+    );
+    for (line_no, line) in input.trim().lines().enumerate() {
+        let machine = match Machine::parse(line) {
+            Ok(machine) => machine,
+            Err(err) => {
+                println!("{:>4}  {}", line_no + 1, err);
+                continue;
+            }
+        };
+        let lights = machine.solve_lights().ok();
+        let joltage = machine.solve_joltage();
+        let check = lights
+            .as_ref()
+            .is_none_or(|s| machine.lights_after(&s.presses) == machine.lights)
+            && joltage
+                .as_ref()
+                .is_none_or(|s| machine.joltage_after(&s.presses) == machine.joltage);
+        let total =
+            |s: &Option<MachineSolution>| s.as_ref().map_or("-".into(), |s| s.total().to_string());
+        let presses =
+            |s: &Option<MachineSolution>| s.as_ref().map_or("-".into(), |s| s.to_string());
+        println!(
+            "{:>4}  {:>6}  {:>6}  {:<5}  {} | {}",
+            line_no + 1,
+            total(&lights),
+            total(&joltage),
+            if check { "ok" } else { "FAIL" },
+            presses(&lights),
+            presses(&joltage)
+        );
+    }
+}
+
 fn main() {
     let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
         .parent()
@@ -144,11 +91,20 @@ fn main() {
         .join("day10.dat");
     let input = std::fs::read_to_string(path).unwrap();
     let t = Instant::now();
-    println!("Part 1: {}", part1(&input));
+    match part1(&input) {
+        Ok(presses) => println!("Part 1: {}", presses),
+        Err(err) => println!("Part 1 failed: {}", err),
+    }
     println!("Part 1 took: {:?}", t.elapsed());
     let t = Instant::now();
-    println!("Part 2: {}", part2(&input));
+    match part2(&input) {
+        Ok(presses) => println!("Part 2: {}", presses),
+        Err(err) => println!("Part 2 failed: {}", err),
+    }
     println!("Part 2 took: {:?}", t.elapsed());
+    if std::env::args().any(|a| a == "--table") {
+        print_table(&input);
+    }
 }
 
 #[cfg(test)]
@@ -161,11 +117,12 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(EXAMPLE), 7);
+        assert_eq!(part1(EXAMPLE), Ok(7));
+        assert!(part1("[.#] (0) {1,0}").is_err());
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(EXAMPLE), 33);
+        assert_eq!(part2(EXAMPLE), Ok(33));
     }
 }
@@ -0,0 +1,107 @@
+use std::fmt::Write;
+
+use crate::Graph;
+
+pub struct DotOptions<'a> {
+    pub source: &'a str,
+    pub sink: &'a str,
+    pub waypoints: Vec<&'a str>,
+    /// Only keep the nodes and edges lying on at least one source→sink path.
+    pub on_path_only: bool,
+}
+
+impl Graph<'_> {
+    /// Renders the graph in Graphviz DOT format, labelling every node with
+    /// its number of paths from `source` and to `sink`.
+    pub fn to_dot(&self, options: &DotOptions) -> String {
+        let from_source = self.paths_from(options.source);
+        let to_sink = self.paths_to(options.sink);
+        let keep = |i: usize| !options.on_path_only || (from_source[i] > 0 && to_sink[i] > 0);
+
+        let mut out = String::new();
+        writeln!(out, "digraph devices {{").unwrap();
+        writeln!(out, "    rankdir=LR;").unwrap();
+        writeln!(out, "    node [shape=box];").unwrap();
+        for (i, &node) in self.sorted.iter().enumerate().filter(|&(i, _)| keep(i)) {
+            let style = if node == options.source {
+                ", style=filled, fillcolor=palegreen"
+            } else if node == options.sink {
+                ", style=filled, fillcolor=lightcoral"
+            } else if options.waypoints.contains(&node) {
+                ", style=filled, fillcolor=gold"
+            } else {
+                ""
+            };
+            writeln!(
+                out,
+                "    \"{node}\" [label=\"{node}\\nfrom {}: {}\\nto {}: {}\"{style}];",
+                options.source, from_source[i], options.sink, to_sink[i]
+            )
+            .unwrap();
+        }
+        for &node in self.sorted.iter().filter(|&&n| keep(self.index[n])) {
+            for &t in self.successors(node) {
+                if keep(self.index[t]) {
+                    writeln!(out, "    \"{node}\" -> \"{t}\";").unwrap();
+                }
+            }
+        }
+        writeln!(out, "}}").unwrap();
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = r#"svr: aaa bbb
+aaa: fft
+fft: ccc
+bbb: tty
+tty: ccc
+ccc: ddd eee
+ddd: hub
+hub: fff
+eee: dac
+dac: fff
+fff: ggg hhh
+ggg: out
+hhh: out
+zzz: aaa
+"#;
+
+    #[test]
+    fn test_to_dot() {
+        let graph = Graph::parse(EXAMPLE);
+        let options = DotOptions {
+            source: "svr",
+            sink: "out",
+            waypoints: vec!["dac", "fft"],
+            on_path_only: false,
+        };
+        let dot = graph.to_dot(&options);
+        assert!(dot.starts_with("digraph devices {"));
+        assert!(dot.contains("\"svr\" [label=\"svr\\nfrom svr: 1\\nto out: 8\", style=filled"));
+        assert!(dot.contains("\"ccc\" [label=\"ccc\\nfrom svr: 2\\nto out: 4\"]"));
+        assert!(dot.contains(
+            "\"dac\" [label=\"dac\\nfrom svr: 2\\nto out: 2\", style=filled, fillcolor=gold]"
+        ));
+        assert!(dot.contains("\"zzz\" -> \"aaa\";"));
+    }
+
+    #[test]
+    fn test_to_dot_on_path_only() {
+        let graph = Graph::parse(EXAMPLE);
+        let options = DotOptions {
+            source: "svr",
+            sink: "out",
+            waypoints: vec![],
+            on_path_only: true,
+        };
+        let dot = graph.to_dot(&options);
+        assert!(!dot.contains("zzz"));
+        assert!(dot.contains("\"svr\" -> \"aaa\";"));
+        assert!(dot.contains("\"hhh\" -> \"out\";"));
+    }
+}
@@ -4,31 +4,55 @@ use std::{
     time::Instant,
 };
 
+mod dot;
+mod paths;
+mod weighted;
+
+use dot::DotOptions;
+use rand::{SeedableRng, rngs::StdRng};
+
 struct Graph<'a> {
     edges: HashMap<&'a str, Vec<&'a str>>,
+    /// Edge weights, aligned with `edges`; unweighted edges weigh 1.
+    weights: HashMap<&'a str, Vec<i64>>,
     sorted: Vec<&'a str>,
+    index: HashMap<&'a str, usize>,
 }
 
 impl<'a> Graph<'a> {
     fn parse(input: &'a str) -> Self {
         let mut edges: HashMap<&str, Vec<&str>> = HashMap::new();
+        let mut weights: HashMap<&str, Vec<i64>> = HashMap::new();
         let mut all_nodes: Vec<&str> = Vec::new();
         for line in input.trim().lines() {
             let (id_in, out_str) = line.split_once(": ").unwrap();
-            let targets: Vec<&str> = out_str.split_whitespace().collect();
-            edges.insert(id_in, targets);
+            let (targets, target_weights): (Vec<&str>, Vec<i64>) = out_str
+                .split_whitespace()
+                .map(|t| match t.split_once('=') {
+                    Some((name, w)) => (name, w.parse().unwrap()),
+                    None => (t, 1),
+                })
+                .unzip();
             if !all_nodes.contains(&id_in) {
                 all_nodes.push(id_in);
             }
-            for t in out_str.split_whitespace() {
-                if !all_nodes.contains(&t) {
+            for t in &targets {
+                if !all_nodes.contains(t) {
                     all_nodes.push(t);
                 }
             }
+            edges.insert(id_in, targets);
+            weights.insert(id_in, target_weights);
         }
 
         let sorted = Self::topological_sort(&edges, &all_nodes);
-        Self { edges, sorted }
+        let index = sorted.iter().enumerate().map(|(i, &n)| (n, i)).collect();
+        Self {
+            edges,
+            weights,
+            sorted,
+            index,
+        }
     }
 
     fn topological_sort(
@@ -67,21 +91,43 @@ impl<'a> Graph<'a> {
         sorted
     }
 
-    fn count_paths(&self, from: &str, to: &str) -> u64 {
-        let i_from = self.sorted.iter().position(|&n| n == from).unwrap();
-        let i_to = self.sorted.iter().position(|&n| n == to).unwrap();
+    fn successors(&self, node: &str) -> &[&'a str] {
+        self.edges.get(node).map_or(&[], |t| t.as_slice())
+    }
+
+    /// Number of paths from `from` to every node, indexed like `sorted`.
+    fn paths_from(&self, from: &str) -> Vec<u64> {
         let mut c: Vec<u64> = vec![0; self.sorted.len()];
+        let i_from = self.index[from];
         c[i_from] = 1;
-        for i in (i_from + 1)..=i_to {
-            for j in i_from..i {
-                if let Some(targets) = self.edges.get(self.sorted[j])
-                    && targets.contains(&self.sorted[i])
-                {
-                    c[i] += c[j];
-                }
+        for i in i_from..self.sorted.len() {
+            if c[i] == 0 {
+                continue;
+            }
+            for &t in self.successors(self.sorted[i]) {
+                c[self.index[t]] += c[i];
             }
         }
-        c[i_to]
+        c
+    }
+
+    /// Number of paths from every node to `to`, indexed like `sorted`.
+    fn paths_to(&self, to: &str) -> Vec<u64> {
+        let mut c: Vec<u64> = vec![0; self.sorted.len()];
+        let i_to = self.index[to];
+        c[i_to] = 1;
+        for i in (0..i_to).rev() {
+            c[i] = self
+                .successors(self.sorted[i])
+                .iter()
+                .map(|&t| c[self.index[t]])
+                .sum();
+        }
+        c
+    }
+
+    fn count_paths(&self, from: &str, to: &str) -> u64 {
+        self.paths_from(from)[self.index[to]]
     }
 }
 
@@ -102,6 +148,12 @@ fn part2(input: &str) -> u64 {
 }
 
 fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let flag = |name: &str| {
+        args.iter()
+            .position(|a| a == name)
+            .and_then(|i| args.get(i + 1))
+    };
     let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
         .parent()
         .unwrap()
@@ -114,13 +166,78 @@ fn main() {
     let t = Instant::now();
     println!("Part 2: {}", part2(&input));
     println!("Part 2 took: {:?}", t.elapsed());
+    let graph = Graph::parse(&input);
+    let from = flag("--from").map_or("you", |s| s.as_str());
+    let to = flag("--to").map_or("out", |s| s.as_str());
+    if let Some(dot_path) = flag("--dot") {
+        let options = DotOptions {
+            source: from,
+            sink: to,
+            waypoints: flag("--via")
+                .map(|s| s.split(',').collect())
+                .unwrap_or_default(),
+            on_path_only: args.iter().any(|a| a == "--on-path"),
+        };
+        std::fs::write(dot_path, graph.to_dot(&options)).unwrap();
+        println!("Graph written to {}", dot_path);
+    }
+    if let Some(n) = flag("--list-paths") {
+        for path in graph.paths(from, to).take(n.parse().unwrap()) {
+            println!("{}", path.join(" -> "));
+        }
+    }
+    if let Some(k) = flag("--kth-path") {
+        match graph.kth_path(from, to, k.parse().unwrap()) {
+            Some(path) => println!("Path #{}: {}", k, path.join(" -> ")),
+            None => println!("Path #{}: out of range", k),
+        }
+    }
+    if args.iter().any(|a| a == "--weights") {
+        let i_to = graph.index[to];
+        match graph.shortest_paths(from)[i_to] {
+            Some(d) => println!("Shortest {} -> {}: {}", from, to, d),
+            None => println!("No path from {} to {}", from, to),
+        }
+        if let Some(d) = graph.longest_paths(from)[i_to] {
+            println!("Longest {} -> {}: {}", from, to, d);
+        }
+        if let Some((d, n)) = graph.count_min_weight_paths(from, to) {
+            println!("Paths of weight {}: {}", d, n);
+        }
+    }
+    if let Some(p) = flag("--modulo") {
+        let p = match p.parse::<u64>() {
+            Ok(p) if p >= 2 => p,
+            _ => {
+                eprintln!("--modulo needs an integer of at least 2, got `{p}`");
+                std::process::exit(1);
+            }
+        };
+        println!(
+            "Paths {} -> {} mod {}: {}",
+            from,
+            to,
+            p,
+            graph.count_paths_mod(from, to, p)
+        );
+    }
+    if let Some(n) = flag("--sample-paths") {
+        let seed = flag("--seed").map_or(0, |s| s.parse().unwrap());
+        let mut rng = StdRng::seed_from_u64(seed);
+        for _ in 0..n.parse().unwrap() {
+            match graph.random_path(from, to, &mut rng) {
+                Some(path) => println!("{}", path.join(" -> ")),
+                None => println!("No path from {} to {}", from, to),
+            }
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const EXAMPLE_P1: &str = r#"aaa: you hhh
+    pub const EXAMPLE_P1: &str = r#"aaa: you hhh
 you: bbb ccc
 bbb: ddd eee
 ccc: ddd eee fff
@@ -0,0 +1,149 @@
+use rand::Rng;
+
+use crate::Graph;
+
+/// Lazy iterator over all paths between two nodes, in lexicographic order of
+/// node names.
+pub struct Paths<'g, 'a> {
+    graph: &'g Graph<'a>,
+    successors: Vec<Vec<usize>>,
+    sink: usize,
+    stack: Vec<(usize, usize)>,
+}
+
+impl<'a> Iterator for Paths<'_, 'a> {
+    type Item = Vec<&'a str>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (node, next) = self.stack.last_mut()?;
+            let node = *node;
+            if node == self.sink {
+                let path = self
+                    .stack
+                    .iter()
+                    .map(|&(n, _)| self.graph.sorted[n])
+                    .collect();
+                self.stack.pop();
+                return Some(path);
+            }
+            match self.successors[node].get(*next) {
+                Some(&t) => {
+                    *next += 1;
+                    self.stack.push((t, 0));
+                }
+                None => {
+                    self.stack.pop();
+                }
+            }
+        }
+    }
+}
+
+impl<'a> Graph<'a> {
+    /// Successors of every node sorted by name, keeping only those from which
+    /// the sink is reachable.
+    fn ranked_successors(&self, to_sink: &[u64]) -> Vec<Vec<usize>> {
+        self.sorted
+            .iter()
+            .map(|&node| {
+                let mut succ = self
+                    .successors(node)
+                    .iter()
+                    .map(|&t| self.index[t])
+                    .filter(|&t| to_sink[t] > 0)
+                    .collect::<Vec<_>>();
+                succ.sort_by_key(|&t| self.sorted[t]);
+                succ
+            })
+            .collect()
+    }
+
+    pub fn paths<'g>(&'g self, from: &str, to: &str) -> Paths<'g, 'a> {
+        let to_sink = self.paths_to(to);
+        let source = self.index[from];
+        Paths {
+            graph: self,
+            successors: self.ranked_successors(&to_sink),
+            sink: self.index[to],
+            stack: if to_sink[source] > 0 {
+                vec![(source, 0)]
+            } else {
+                vec![]
+            },
+        }
+    }
+
+    /// The `k`-th path (0-based) in the order produced by [`Graph::paths`].
+    pub fn kth_path(&self, from: &str, to: &str, mut k: u64) -> Option<Vec<&'a str>> {
+        let to_sink = self.paths_to(to);
+        let successors = self.ranked_successors(&to_sink);
+        let (mut node, sink) = (self.index[from], self.index[to]);
+        if k >= to_sink[node] {
+            return None;
+        }
+        let mut path = vec![self.sorted[node]];
+        while node != sink {
+            for &t in &successors[node] {
+                if k < to_sink[t] {
+                    node = t;
+                    break;
+                }
+                k -= to_sink[t];
+            }
+            path.push(self.sorted[node]);
+        }
+        Some(path)
+    }
+
+    /// A path drawn uniformly at random among all paths from `from` to `to`.
+    pub fn random_path(&self, from: &str, to: &str, rng: &mut impl Rng) -> Option<Vec<&'a str>> {
+        match self.count_paths(from, to) {
+            0 => None,
+            total => self.kth_path(from, to, rng.random_range(0..total)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use super::*;
+    use crate::tests::EXAMPLE_P1;
+
+    #[test]
+    fn test_paths() {
+        let graph = Graph::parse(EXAMPLE_P1);
+        let paths = graph.paths("you", "out").collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            vec![
+                vec!["you", "bbb", "ddd", "ggg", "out"],
+                vec!["you", "bbb", "eee", "out"],
+                vec!["you", "ccc", "ddd", "ggg", "out"],
+                vec!["you", "ccc", "eee", "out"],
+                vec!["you", "ccc", "fff", "out"],
+            ]
+        );
+        for (k, path) in paths.iter().enumerate() {
+            assert_eq!(graph.kth_path("you", "out", k as u64).as_ref(), Some(path));
+        }
+        assert_eq!(graph.kth_path("you", "out", 5), None);
+        assert_eq!(graph.paths("ddd", "fff").count(), 0);
+    }
+
+    #[test]
+    fn test_random_path() {
+        let graph = Graph::parse(EXAMPLE_P1);
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut hits = [0; 5];
+        for _ in 0..5000 {
+            let path = graph.random_path("you", "out", &mut rng).unwrap();
+            let k = graph.paths("you", "out").position(|p| p == path).unwrap();
+            hits[k] += 1;
+        }
+        assert!(hits.iter().all(|&h| (800..1200).contains(&h)));
+        assert_eq!(graph.random_path("ddd", "fff", &mut rng), None);
+    }
+}
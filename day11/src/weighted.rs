@@ -0,0 +1,123 @@
+use crate::Graph;
+
+impl<'a> Graph<'a> {
+    fn weighted_successors(&self, node: &str) -> impl Iterator<Item = (usize, i64)> + '_ {
+        let weights = self.weights.get(node).map_or(&[][..], |w| w.as_slice());
+        self.successors(node)
+            .iter()
+            .zip(weights)
+            .map(|(&t, &w)| (self.index[t], w))
+    }
+
+    /// Relaxes every edge reachable from `from` in topological order, keeping
+    /// the distance preferred by `better`. Indexed like `sorted`.
+    fn relax_from(&self, from: &str, better: impl Fn(i64, i64) -> bool) -> Vec<Option<i64>> {
+        let mut dist: Vec<Option<i64>> = vec![None; self.sorted.len()];
+        let i_from = self.index[from];
+        dist[i_from] = Some(0);
+        for i in i_from..self.sorted.len() {
+            let Some(d) = dist[i] else { continue };
+            for (t, w) in self.weighted_successors(self.sorted[i]) {
+                if dist[t].is_none_or(|dt| better(d + w, dt)) {
+                    dist[t] = Some(d + w);
+                }
+            }
+        }
+        dist
+    }
+
+    /// Minimum path weight from `from` to every node, indexed like `sorted`.
+    pub fn shortest_paths(&self, from: &str) -> Vec<Option<i64>> {
+        self.relax_from(from, |a, b| a < b)
+    }
+
+    /// Maximum path weight from `from` to every node, indexed like `sorted`.
+    pub fn longest_paths(&self, from: &str) -> Vec<Option<i64>> {
+        self.relax_from(from, |a, b| a > b)
+    }
+
+    /// Number of paths from `from` to `to` modulo `p`. Panics if `p` is
+    /// below 2.
+    pub fn count_paths_mod(&self, from: &str, to: &str, p: u64) -> u64 {
+        assert!(p >= 2, "path counts need a modulus of at least 2");
+        let mut c: Vec<u64> = vec![0; self.sorted.len()];
+        let i_from = self.index[from];
+        c[i_from] = 1;
+        for i in i_from..self.sorted.len() {
+            for &t in self.successors(self.sorted[i]) {
+                let t = self.index[t];
+                // Both counts are below `p`, but their sum may not fit a u64.
+                c[t] = ((c[t] as u128 + c[i] as u128) % p as u128) as u64;
+            }
+        }
+        c[self.index[to]]
+    }
+
+    /// Weight of the lightest path from `from` to `to` together with the
+    /// number of paths achieving it.
+    pub fn count_min_weight_paths(&self, from: &str, to: &str) -> Option<(i64, u64)> {
+        let dist = self.shortest_paths(from);
+        let mut c: Vec<u64> = vec![0; self.sorted.len()];
+        let i_from = self.index[from];
+        c[i_from] = 1;
+        for i in i_from..self.sorted.len() {
+            let Some(d) = dist[i] else { continue };
+            for (t, w) in self.weighted_successors(self.sorted[i]) {
+                if dist[t] == Some(d + w) {
+                    c[t] += c[i];
+                }
+            }
+        }
+        let i_to = self.index[to];
+        dist[i_to].map(|d| (d, c[i_to]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::EXAMPLE_P1;
+
+    const WEIGHTED: &str = r#"you: bbb=2 ccc=1
+bbb: ddd=1 eee=3
+ccc: ddd=2 eee=4 fff=9
+ddd: ggg=1
+eee: out=1
+fff: out
+ggg: out=3"#;
+
+    #[test]
+    fn test_parse_weights() {
+        let graph = Graph::parse(WEIGHTED);
+        assert_eq!(graph.edges["ccc"], vec!["ddd", "eee", "fff"]);
+        assert_eq!(graph.weights["ccc"], vec![2, 4, 9]);
+        assert_eq!(graph.weights["fff"], vec![1]);
+        assert_eq!(graph.count_paths("you", "out"), 5);
+    }
+
+    #[test]
+    fn test_shortest_longest() {
+        let graph = Graph::parse(WEIGHTED);
+        let out = graph.index["out"];
+        assert_eq!(graph.shortest_paths("you")[out], Some(6));
+        assert_eq!(graph.longest_paths("you")[out], Some(11));
+        assert_eq!(graph.shortest_paths("ddd")[graph.index["fff"]], None);
+        assert_eq!(graph.count_min_weight_paths("you", "out"), Some((6, 2)));
+        assert_eq!(graph.count_min_weight_paths("ddd", "fff"), None);
+    }
+
+    #[test]
+    fn test_count_paths_mod() {
+        let graph = Graph::parse(EXAMPLE_P1);
+        assert_eq!(graph.count_paths_mod("you", "out", 1_000_000_007), 5);
+        assert_eq!(graph.count_paths_mod("you", "out", 3), 2);
+        assert_eq!(graph.count_paths_mod("you", "out", u64::MAX), 5);
+        assert_eq!(graph.count_min_weight_paths("you", "out"), Some((3, 3)));
+    }
+
+    #[test]
+    #[should_panic(expected = "at least 2")]
+    fn test_count_paths_mod_zero() {
+        Graph::parse(EXAMPLE_P1).count_paths_mod("you", "out", 0);
+    }
+}